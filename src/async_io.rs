@@ -0,0 +1,256 @@
+use std::io::{
+    Cursor,
+    Error,
+    ErrorKind,
+    Read,
+    SeekFrom,
+};
+
+use tokio::io::{
+    AsyncRead,
+    AsyncReadExt,
+    AsyncSeek,
+    AsyncSeekExt,
+};
+
+use crate::blorb::{
+    Chunk,
+    ResourceIndex,
+    Usage,
+};
+use crate::error::{
+    BlorbError,
+    Result,
+};
+use crate::io::{
+    BlorbOptions,
+    MAX_LEADING_JUNK,
+    ReadBlorbExt,
+    find_form_header,
+    read_index,
+};
+
+
+/// Asynchronous counterpart of `BlorbCursor`, for reading blorbs from
+/// sources implementing tokio's `AsyncRead` and `AsyncSeek`.
+///
+/// Only the resource index is loaded when the cursor is created. When
+/// `AsyncBlorbCursor::load_resource` is called, the chunk of the
+/// resource is read from the source without blocking, and parsed into
+/// the same `Chunk` returned by `BlorbCursor::load_resource`.
+pub struct AsyncBlorbCursor<R: AsyncRead + AsyncSeek + Unpin> {
+    /// The length of the blorb, minus the 8 byte chunk header.
+    pub len: u32,
+    index: ResourceIndex,
+    base: u64,
+    file: R,
+}
+
+
+impl<R: AsyncRead + AsyncSeek + Unpin> AsyncBlorbCursor<R> {
+
+    /// Returns an `AsyncBlorbCursor` using the given blorb source. The
+    /// blorb is parsed and validated as with `BlorbCursor::from_file`.
+    /// A `BlorbError` is returned if an error occurs with accessing the
+    /// source or if the source is not a valid blorb.
+    pub async fn from_file(src: R) -> Result<AsyncBlorbCursor<R>> {
+        AsyncBlorbCursor::from_file_with_options(src, BlorbOptions::default())
+            .await
+    }
+
+    /// Returns an `AsyncBlorbCursor` using the given blorb source, opened
+    /// using the given `BlorbOptions`. The blorb is parsed and validated
+    /// as with `BlorbCursor::from_file_with_options`.
+    pub async fn from_file_with_options(src: R, options: BlorbOptions)
+            -> Result<AsyncBlorbCursor<R>> {
+        let mut src = src;
+        let mut start = src.stream_position().await?;
+        if options.skip_leading_junk {
+            let mut head = Vec::new();
+            (&mut src).take(MAX_LEADING_JUNK + 0xC)
+                .read_to_end(&mut head).await?;
+            if let Some(offset) = find_form_header(&mut &head[..])? {
+                start += offset;
+            }
+            src.seek(SeekFrom::Start(start)).await?;
+        }
+
+        // validate the file is a blorb form. A source ending inside the
+        // form header is reported with the number of bytes available.
+        let mut header = [0x0; 0xC];
+        match src.read_exact(&mut header).await {
+            Err(ref err) if err.kind() == ErrorKind::UnexpectedEof => {
+                let end = src.seek(SeekFrom::End(0)).await?;
+                return Err(BlorbError::TooShortForBlorb{
+                    available: end.saturating_sub(start),
+                });
+            },
+            read => {read?;},
+        }
+        let form = (&header[..]).read_form_data()?;
+        if &form.id != b"IFRS" {
+            return Err(BlorbError::NotBlorb);
+        }
+
+        // load the index chunk into memory, then validate it as with
+        // `BlorbCursor`.
+        let mut header = [0x0; 0x8];
+        src.read_exact(&mut header).await?;
+        let meta = (&header[..]).read_chunk_data()?;
+        let data = read_exact_vec(&mut src, meta.len).await?;
+        let mut chunk = Read::chain(&header[..], &data[..]);
+        let index = read_index(&mut chunk, &options)?;
+        Ok(AsyncBlorbCursor{len: form.len, index, base: start, file: src})
+    }
+
+    /// Using the given index, looks up a blorb resource and loads the
+    /// resource chunk into memory. This chunk is then returned to the
+    /// caller. A `BlorbError` is returned if there is an exception
+    /// while loading the resource into memory, if the loaded data is
+    /// invalid, or if a resource is requested which is not identified
+    /// in the `ResourceIndex`.
+    pub async fn load_resource(&mut self, usage: Usage, index: u32)
+            -> Result<Chunk> {
+        let start = match self.index.get(usage, index) {
+            Some(entry) => entry.start,
+            None => return Err(BlorbError::NotFound),
+        };
+        self.file.seek(SeekFrom::Start(self.base + start as u64)).await?;
+        read_chunk(&mut self.file).await
+    }
+
+    /// Unwraps the `AsyncBlorbCursor`, returning the underlying source.
+    pub fn into_inner(self) -> R {
+        self.file
+    }
+}
+
+
+/// Reads the chunk at the current position of the source into memory,
+/// then parses it into a `Chunk`. The pad byte following odd length
+/// chunks is left unread. A `BlorbError` is returned if an error occurs
+/// reading from the source, or if the chunk data is not valid.
+async fn read_chunk<R: AsyncRead + Unpin>(src: &mut R) -> Result<Chunk> {
+    let mut header = [0x0; 0x8];
+    src.read_exact(&mut header).await?;
+    let meta = (&header[..]).read_chunk_data()?;
+
+    let data = read_exact_vec(src, meta.len).await?;
+    Cursor::new(data).read_from_chunk_data(meta)
+}
+
+
+/// Reads `len` bytes from the source into a `Vec<u8>`, as with
+/// `ReadBlorbExt::read_exact_vec`. If the source ends before `len` bytes
+/// are read, a `BlorbError` is returned.
+async fn read_exact_vec<R: AsyncRead + Unpin>(src: &mut R, len: u32)
+        -> Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(len as usize);
+    if len as usize != src.take(len as u64).read_to_end(&mut data).await? {
+        return Err(Error::new(
+            ErrorKind::UnexpectedEof,
+            "file ended before chunk fully read").into());
+    }
+    Ok(data)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+
+    use tokio::runtime::Builder;
+
+    use crate::testutil::{
+        Corruption,
+        build_blorb,
+        corrupt_blorb,
+    };
+    use super::*;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    #[test]
+    fn load_resource_in_memory() {
+        let blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 1, b"OGGV", b"ogg data"),
+        ], &[]);
+
+        let (png, ogg) = block_on(async {
+            let mut cursor = AsyncBlorbCursor::from_file(Cursor::new(blorb))
+                .await.unwrap();
+            let png = cursor.load_resource(Usage::Pict, 1).await.unwrap();
+            let ogg = cursor.load_resource(Usage::Snd, 1).await.unwrap();
+            (png, ogg)
+        });
+        assert_eq!(png, Chunk::Png{data: b"png".to_vec()});
+        assert_eq!(ogg, Chunk::Ogg{data: b"ogg data".to_vec()});
+    }
+
+    #[test]
+    fn load_resource_not_found() {
+        let blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+
+        let result = block_on(async {
+            let mut cursor = AsyncBlorbCursor::from_file(Cursor::new(blorb))
+                .await.unwrap();
+            cursor.load_resource(Usage::Pict, 2).await
+        });
+        match result {
+            Err(BlorbError::NotFound) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_file_with_options_skips_junk_and_rejects_duplicates() {
+        let valid = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Pict", 2, b"PNG ", b"png"),
+        ], &[]);
+        let mut blorb = b"junk".to_vec();
+        blorb.extend(corrupt_blorb(&valid, Corruption::DuplicateEntry));
+        let options = BlorbOptions{
+            skip_leading_junk: true,
+            reject_duplicate_entries: true,
+        };
+
+        let result = block_on(AsyncBlorbCursor::from_file_with_options(
+            Cursor::new(blorb), options));
+        match result {
+            Err(BlorbError::DuplicateEntry{usage: Usage::Pict, num: 1}) => (),
+            Err(err) => panic!("unexpected error: {:?}", err),
+            Ok(_) => panic!("duplicate entry was not rejected"),
+        }
+
+        let mut blorb = b"junk".to_vec();
+        blorb.extend(valid);
+        let png = block_on(async {
+            let mut cursor = AsyncBlorbCursor::from_file_with_options(
+                Cursor::new(blorb), options).await.unwrap();
+            cursor.load_resource(Usage::Pict, 2).await.unwrap()
+        });
+        assert_eq!(png, Chunk::Png{data: b"png".to_vec()});
+    }
+
+    #[test]
+    fn load_resource_truncated() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        let len = blorb.len();
+        blorb.truncate(len - 0x2);
+
+        let result = block_on(async {
+            let mut cursor = AsyncBlorbCursor::from_file(Cursor::new(blorb))
+                .await.unwrap();
+            cursor.load_resource(Usage::Pict, 1).await
+        });
+        match result {
+            Err(BlorbError::Io(ref err))
+                if err.kind() == ErrorKind::UnexpectedEof => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -1,4 +1,10 @@
 use std::collections::HashMap;
+use std::fmt;
+
+use byteorder::{
+    BigEndian,
+    ByteOrder,
+};
 
 // Metadata Structs
 ////////////////////////////////////////////////////////////////////////
@@ -12,7 +18,7 @@ use std::collections::HashMap;
 ///
 /// **NOTE**: The `len` includes the 4 bytes in `id`. The remaining
 /// length of the chunk after the `id` is `len - 4`.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct FormData {
     /// the length of the form, not counting the 8 byte chunk header
     pub len: u32,
@@ -23,7 +29,7 @@ pub struct FormData {
 
 /// Container for chunk metadata. Used for identifying a chunk without
 /// loading the full chunk into memory.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ChunkData {
     /// The 4 byte ascii id of the chunk
     pub id: [u8; 0x4],
@@ -44,7 +50,7 @@ impl From<FormData> for ChunkData {
 
 
 /// The usage information for an `IndexEntry`.
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Usage {
     /// Identifier: `b"Pict"`.
     /// Indicates the resource is an image.
@@ -64,9 +70,36 @@ pub enum Usage {
 }
 
 
+impl Usage {
+
+    /// Returns the four byte identifier of the usage, as stored in the
+    /// resource index.
+    pub fn as_bytes(&self) -> [u8; 0x4] {
+        match *self {
+            Usage::Pict => *b"Pict",
+            Usage::Snd => *b"Snd ",
+            Usage::Data => *b"Data",
+            Usage::Exec => *b"Exec",
+        }
+    }
+
+    /// Returns the usage with the given four byte identifier, or `None`
+    /// if the identifier is not a recognized usage.
+    pub fn from_bytes(id: &[u8; 0x4]) -> Option<Usage> {
+        match id {
+            b"Pict" => Some(Usage::Pict),
+            b"Snd " => Some(Usage::Snd),
+            b"Data" => Some(Usage::Data),
+            b"Exec" => Some(Usage::Exec),
+            _ => None,
+        }
+    }
+}
+
+
 /// Contains the usage information for an entry, the resource number of
 /// the entry, and where in the blob the entry starts.
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct IndexEntry {
     /// The type of the resource
     pub usage: Usage,
@@ -77,8 +110,116 @@ pub struct IndexEntry {
 }
 
 
+/// The kind of virtual machine, or native code, held by the executable
+/// resource of a blorb. Each kind mirrors one of the executable resource
+/// chunk identifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecKind {
+    /// Identifier: `b"ZCOD"`.
+    ZCode,
+    /// Identifier: `b"GLUL"`.
+    Glulx,
+    /// Identifier: `b"TAD2"`.
+    Tads2,
+    /// Identifier: `b"TAD3"`.
+    Tads3,
+    /// Identifier: `b"HUGO"`.
+    Hugo,
+    /// Identifier: `b"ALAN"`.
+    Alan,
+    /// Identifier: `b"ADRI"`.
+    Adrift,
+    /// Identifier: `b"LEVE"`.
+    Level9,
+    /// Identifier: `b"AGT "`.
+    Agt,
+    /// Identifier: `b"MAGS"`.
+    MagneticScrolls,
+    /// Identifier: `b"ADVS"`.
+    AdvSys,
+    /// Identifier: `b"EXEC"`.
+    Native,
+    /// An executable chunk with an unrecognized identifier.
+    Unknown{id: [u8; 0x4]},
+}
+
+
+impl ExecKind {
+
+    /// Returns the `ExecKind` matching the given executable chunk
+    /// identifier. Unrecognized identifiers return `ExecKind::Unknown`.
+    pub fn from_id(id: &[u8; 0x4]) -> ExecKind {
+        match id {
+            b"ZCOD" => ExecKind::ZCode,
+            b"GLUL" => ExecKind::Glulx,
+            b"TAD2" => ExecKind::Tads2,
+            b"TAD3" => ExecKind::Tads3,
+            b"HUGO" => ExecKind::Hugo,
+            b"ALAN" => ExecKind::Alan,
+            b"ADRI" => ExecKind::Adrift,
+            b"LEVE" => ExecKind::Level9,
+            b"AGT " => ExecKind::Agt,
+            b"MAGS" => ExecKind::MagneticScrolls,
+            b"ADVS" => ExecKind::AdvSys,
+            b"EXEC" => ExecKind::Native,
+            _ => ExecKind::Unknown{id: *id},
+        }
+    }
+}
+
+
+/// The format of the image data held by a picture resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Identifier: `b"PNG "`.
+    Png,
+    /// Identifier: `b"JPEG"`.
+    Jpeg,
+    /// Identifier: `b"GIF "`.
+    Gif,
+}
+
+
+/// Scaling information for a scalable image, from the resolution
+/// chunk. Each ratio is given as a numerator and denominator. A minimum
+/// ratio of `0/0` indicates no minimum, and a maximum ratio of `0/0`
+/// indicates no maximum.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImageResolution {
+    /// The resource number of the picture being scaled
+    pub num: u32,
+    /// The numerator of the standard ratio
+    pub ratnum: u32,
+    /// The denominator of the standard ratio
+    pub ratden: u32,
+    /// The numerator of the minimum ratio
+    pub minnum: u32,
+    /// The denominator of the minimum ratio
+    pub minden: u32,
+    /// The numerator of the maximum ratio
+    pub maxnum: u32,
+    /// The denominator of the maximum ratio
+    pub maxden: u32,
+}
+
+
+/// Basic information about a module sound, read from the header of a
+/// MOD or SONG chunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SoundInfo {
+    /// The number of channels played at once
+    pub channels: u16,
+    /// The number of instrument samples the module can hold, 15 or 31
+    pub samples: u16,
+    /// The number of patterns stored in the module
+    pub patterns: u16,
+    /// The number of pattern positions played in the song
+    pub positions: u16,
+}
+
+
 /// Container for list of resource index entries.
-#[derive(Debug)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ResourceIndex {
     /// a map of index value of a picture to the index entry of the
     /// resource.
@@ -91,10 +232,70 @@ pub struct ResourceIndex {
     pub data: HashMap<usize, IndexEntry>,
     /// an optional containing the exec index entry, if it is present
     pub exec: Option<IndexEntry>,
+    /// the number of entries declared by the header of the `RIdx`
+    /// chunk, which is 0 for an index not read from a blorb. This
+    /// exceeds the number of entries held if the index has entries
+    /// sharing the same usage and resource number.
+    pub declared_count: u32,
+}
+
+
+impl ResourceIndex {
+
+    /// Returns an empty `ResourceIndex`.
+    pub fn new() -> ResourceIndex {
+        ResourceIndex::default()
+    }
+
+    /// Inserts the entry into the map for its usage, replacing any entry
+    /// with the same usage and resource number. As a blorb holds at
+    /// most one executable, an `Exec` entry replaces any previous
+    /// `Exec` entry regardless of its resource number.
+    pub fn insert(&mut self, entry: IndexEntry) {
+        let num = entry.num as usize;
+        match entry.usage {
+            Usage::Pict => {self.pictures.insert(num, entry);},
+            Usage::Snd => {self.sounds.insert(num, entry);},
+            Usage::Data => {self.data.insert(num, entry);},
+            Usage::Exec => self.exec = Some(entry),
+        }
+    }
+
+    /// Returns the entry with the given usage and resource number, or
+    /// `None` if there is no such entry. As a blorb holds at most one
+    /// executable, the resource number is not considered for `Exec`.
+    pub fn get(&self, usage: Usage, num: u32) -> Option<&IndexEntry> {
+        match usage {
+            Usage::Pict => self.pictures.get(&(num as usize)),
+            Usage::Snd => self.sounds.get(&(num as usize)),
+            Usage::Data => self.data.get(&(num as usize)),
+            Usage::Exec => self.exec.as_ref(),
+        }
+    }
+
+    /// Returns an iterator over every entry in the index. Entries are
+    /// ordered by usage, with pictures first, followed by sounds, data,
+    /// and then the exec entry. Entries of the same usage are ordered
+    /// by resource number.
+    pub fn entries(&self) -> ::std::vec::IntoIter<&IndexEntry> {
+        let mut entries = Vec::with_capacity(self.pictures.len()
+            + self.sounds.len() + self.data.len() + 1);
+        for map in &[&self.pictures, &self.sounds, &self.data] {
+            let start = entries.len();
+            entries.extend(map.values());
+            entries[start..].sort_by_key(|entry| entry.num);
+        }
+        entries.extend(self.exec.as_ref());
+        entries.into_iter()
+    }
 }
 
 
 /// Representation for loaded blorb chunks
+///
+/// The `Debug` output of a chunk shows the length of any byte payload,
+/// rather than the bytes themselves, to keep logged chunks readable.
+#[derive(Clone, PartialEq, Eq)]
 pub enum Chunk {
 
     /// Chunk returned when the loaded chunk type is unable to be
@@ -115,6 +316,7 @@ pub enum Chunk {
 
     /// Identifier: `b"IFmd"`.
     /// Contains xml metadata content for the IF.
+    /// The common fields can be parsed using `IfMetadata::parse`.
     Metadata{info: String},
 
     /// Identifier: `b"Fspc"`.
@@ -122,6 +324,69 @@ pub enum Chunk {
     /// This chunk is optional.
     Frontispiece{num: u32},
 
+    /// Identifier: `b"Reso"`.
+    /// Contains the standard, minimum, and maximum window sizes, and a
+    /// map of picture resource number to the scaling information of
+    /// each scalable picture. A window size of 0 indicates no limit.
+    /// This chunk is optional, and only meaningful for Z-code.
+    Resolution{
+        px: u32, py: u32,
+        minx: u32, miny: u32,
+        maxx: u32, maxy: u32,
+        images: HashMap<usize, ImageResolution>,
+    },
+
+    /// Identifier: `b"AUTH"`.
+    /// Contains the name of the author of the IF.
+    /// This chunk is optional.
+    Author{text: String},
+
+    /// Identifier: `b"(c) "`.
+    /// Contains the copyright message of the IF.
+    /// This chunk is optional.
+    Copyright{text: String},
+
+    /// Identifier: `b"ANNO"`.
+    /// Contains an annotation, with any text at all, for the IF.
+    /// This chunk is optional.
+    Annotation{text: String},
+
+    /// Identifier: `b"RelN"`.
+    /// Contains the release number of the IF.
+    /// This chunk is optional.
+    ReleaseNumber{number: u16},
+
+    /// Identifier: `b"APal"`.
+    /// Contains the picture resource numbers of images whose palettes
+    /// should adapt to the colors in use on the display.
+    /// This chunk is optional.
+    AdaptivePalette{entries: Vec<u32>},
+
+    /// Identifier: `b"Plte"`.
+    /// Contains a suggested palette of red, green, and blue colors for
+    /// drawing placeholder rectangles.
+    /// This chunk is optional.
+    Palette{colors: Vec<(u8, u8, u8)>},
+
+    /// Identifier: `b"Plte"`.
+    /// Contains the color depth, either 16 or 32 bits, suggested for
+    /// displaying placeholder rectangles in direct color rather than
+    /// through a palette.
+    /// This chunk is optional.
+    DirectPalette{depth: u8},
+
+    /// Identifier: `b"Dbug"`.
+    /// Contains debugging information for the executable, as emitted by
+    /// the compiler.
+    /// This chunk is optional.
+    Debug{data: Vec<u8>},
+
+    /// Identifier: `b"SNam"`.
+    /// Contains the short name of the IF, as UTF-16 text with big
+    /// endian code units. This chunk is optional, and deprecated in
+    /// favor of the metadata chunk.
+    ShortName{name: String},
+
     /// Identifier: `b"ZCOD"`.
     /// Contains Z-code executable.
     /// This is an executable resource chunk.
@@ -247,3 +512,785 @@ pub enum Chunk {
     /// this is a sound resource chunk for ADRIFT blorbs.
     Mp3{data: Vec<u8>},
 }
+
+
+impl Chunk {
+
+    /// Returns the width and height, in pixels, of a picture chunk.
+    /// For `Chunk::Png`, these are read from the `IHDR` chunk, and for
+    /// `Chunk::Jpeg`, these are read from the start of frame marker.
+    /// For `Chunk::Rectangle`, the rectangle's dimensions are returned.
+    /// `None` is returned for other chunks, or if the image data is
+    /// malformed.
+    pub fn image_dimensions(&self) -> Option<(u32, u32)> {
+        match *self {
+            Chunk::Png{ref data} => png_dimensions(data),
+            Chunk::Jpeg{ref data} => jpeg_dimensions(data),
+            Chunk::Rectangle{width, height} => Some((width, height)),
+            _ => None,
+        }
+    }
+
+    /// Returns the four byte identifier of the chunk, as stored in the
+    /// blorb. For forms, such as `Chunk::Aiff`, this is the form type
+    /// rather than `b"FORM"`. For `Chunk::Unknown` and
+    /// `Chunk::UnknownForm`, the identifier read from the blorb is
+    /// returned.
+    pub fn id(&self) -> [u8; 0x4] {
+        match *self {
+            Chunk::Unknown{ref meta, ..} => meta.id,
+            Chunk::UnknownForm{ref meta, ..} => meta.id,
+            Chunk::ResourceIndex{..} => *b"RIdx",
+            Chunk::Metadata{..} => *b"IFmd",
+            Chunk::Frontispiece{..} => *b"Fspc",
+            Chunk::Resolution{..} => *b"Reso",
+            Chunk::Author{..} => *b"AUTH",
+            Chunk::Copyright{..} => *b"(c) ",
+            Chunk::Annotation{..} => *b"ANNO",
+            Chunk::ReleaseNumber{..} => *b"RelN",
+            Chunk::AdaptivePalette{..} => *b"APal",
+            Chunk::Palette{..} => *b"Plte",
+            Chunk::DirectPalette{..} => *b"Plte",
+            Chunk::Debug{..} => *b"Dbug",
+            Chunk::ShortName{..} => *b"SNam",
+            Chunk::ZCode{..} => *b"ZCOD",
+            Chunk::Glulx{..} => *b"GLUL",
+            Chunk::Tads2{..} => *b"TAD2",
+            Chunk::Tads3{..} => *b"TAD3",
+            Chunk::Hugo{..} => *b"HUGO",
+            Chunk::Alan{..} => *b"ALAN",
+            Chunk::Adrift{..} => *b"ADRI",
+            Chunk::Level9{..} => *b"LEVE",
+            Chunk::Agt{..} => *b"AGT ",
+            Chunk::MagneticScrolls{..} => *b"MAGS",
+            Chunk::AdvSys{..} => *b"ADVS",
+            Chunk::Exec{..} => *b"EXEC",
+            Chunk::Png{..} => *b"PNG ",
+            Chunk::Jpeg{..} => *b"JPEG",
+            Chunk::Rectangle{..} => *b"Rect",
+            Chunk::Aiff{..} => *b"AIFF",
+            Chunk::Ogg{..} => *b"OGGV",
+            Chunk::Mod{..} => *b"MOD ",
+            Chunk::Song{..} => *b"SONG",
+            Chunk::Text{..} => *b"TEXT",
+            Chunk::Binary{..} => *b"BINA",
+            Chunk::Gif{..} => *b"GIF ",
+            Chunk::Wav{..} => *b"WAV ",
+            Chunk::Midi{..} => *b"MIDI",
+            Chunk::Mp3{..} => *b"MP3 ",
+        }
+    }
+
+    /// Returns basic information about a module sound, read from the
+    /// header of a `Chunk::Mod` or `Chunk::Song`. Modules using the
+    /// original 15 sample layout, without a format signature, are
+    /// assumed to have 4 channels. `None` is returned for other chunks,
+    /// or if the module header is malformed.
+    pub fn sound_info(&self) -> Option<SoundInfo> {
+        match *self {
+            Chunk::Mod{ref data} | Chunk::Song{ref data} => module_info(data),
+            _ => None,
+        }
+    }
+
+    /// Derives the IFID of an executable chunk, as described by the
+    /// Treaty of Babel. A UUID embedded in the executable as
+    /// `UUID://...//` is used if present. Otherwise, for `Chunk::ZCode`
+    /// the IFID is built from the release number, serial number, and
+    /// checksum of the story file header, and for a `Chunk::Glulx`
+    /// compiled by Inform, from the release number, serial number, and
+    /// checksum of its header. `None` is returned for other chunks, or
+    /// if the header is malformed.
+    pub fn ifid(&self) -> Option<String> {
+        let code = match *self {
+            Chunk::ZCode{ref code}
+            | Chunk::Glulx{ref code} => code,
+            _ => return None,
+        };
+        if let Some(uuid) = embedded_uuid(code) {
+            return Some(uuid);
+        }
+        match *self {
+            Chunk::ZCode{..} => zcode_ifid(code),
+            _ => glulx_ifid(code),
+        }
+    }
+
+    /// Returns the byte payload of chunks which hold file data, such as
+    /// images, sounds, executables, and binary or text data. For
+    /// `Chunk::Aiff`, this includes the full `FORM` envelope, and for
+    /// `Chunk::Text`, this is the text as utf-8. `None` is returned for
+    /// structural chunks, such as `Chunk::ResourceIndex`,
+    /// `Chunk::Frontispiece`, or `Chunk::Rectangle`.
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match *self {
+            Chunk::Unknown{ref data, ..}
+            | Chunk::UnknownForm{ref data, ..}
+            | Chunk::Png{ref data}
+            | Chunk::Jpeg{ref data}
+            | Chunk::Gif{ref data}
+            | Chunk::Aiff{ref data}
+            | Chunk::Ogg{ref data}
+            | Chunk::Mod{ref data}
+            | Chunk::Song{ref data}
+            | Chunk::Wav{ref data}
+            | Chunk::Midi{ref data}
+            | Chunk::Mp3{ref data}
+            | Chunk::Binary{ref data}
+            | Chunk::Debug{ref data} => Some(data),
+            Chunk::ZCode{ref code}
+            | Chunk::Glulx{ref code}
+            | Chunk::Tads2{ref code}
+            | Chunk::Tads3{ref code}
+            | Chunk::Hugo{ref code}
+            | Chunk::Alan{ref code}
+            | Chunk::Adrift{ref code}
+            | Chunk::Level9{ref code}
+            | Chunk::Agt{ref code}
+            | Chunk::MagneticScrolls{ref code}
+            | Chunk::AdvSys{ref code}
+            | Chunk::Exec{ref code} => Some(code),
+            Chunk::Text{ref text} => Some(text.as_bytes()),
+            _ => None,
+        }
+    }
+
+    /// Returns the conventional file extension for the data held by a
+    /// resource chunk, such as `"png"` or `"ulx"`. For `Chunk::ZCode`,
+    /// the extension reflects the story file version held in its first
+    /// byte, such as `"z5"`. `None` is returned for chunks which do not
+    /// hold file data, such as `Chunk::ResourceIndex` or
+    /// `Chunk::Rectangle`, and for unrecognized chunks.
+    pub fn file_extension(&self) -> Option<&'static str> {
+        match *self {
+            Chunk::ZCode{ref code} => match code.first() {
+                Some(&1) => Some("z1"),
+                Some(&2) => Some("z2"),
+                Some(&3) => Some("z3"),
+                Some(&4) => Some("z4"),
+                Some(&5) => Some("z5"),
+                Some(&6) => Some("z6"),
+                Some(&7) => Some("z7"),
+                Some(&8) => Some("z8"),
+                _ => Some("zcode"),
+            },
+            _ => id_file_extension(&self.id()),
+        }
+    }
+}
+
+
+/// Returns the conventional file extension for data held in a chunk,
+/// or form, with the given identifier. Z-code is given the generic
+/// `zcode`, as its version is not known from the identifier. `None` is
+/// returned for identifiers of chunks which do not hold file data, and
+/// for unrecognized identifiers.
+pub(crate) fn id_file_extension(id: &[u8; 0x4]) -> Option<&'static str> {
+    let extension = match id {
+        b"ZCOD" => "zcode",
+        b"GLUL" => "ulx",
+        b"TAD2" => "gam",
+        b"TAD3" => "t3",
+        b"HUGO" => "hex",
+        b"ALAN" => "acd",
+        b"ADRI" => "taf",
+        b"LEVE" => "l9",
+        b"AGT " => "agx",
+        b"MAGS" => "mag",
+        b"ADVS" => "dat",
+        b"PNG " => "png",
+        b"JPEG" => "jpeg",
+        b"GIF " => "gif",
+        b"AIFF" => "aiff",
+        b"OGGV" => "ogg",
+        b"MOD " => "mod",
+        b"SONG" => "song",
+        b"WAV " => "wav",
+        b"MIDI" => "mid",
+        b"MP3 " => "mp3",
+        b"TEXT" => "txt",
+        b"BINA" => "bin",
+        _ => return None,
+    };
+    Some(extension)
+}
+
+
+/// Reads the image dimensions from the `IHDR` chunk of a PNG, which
+/// must directly follow the PNG signature.
+fn png_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 0x18
+            || &data[0x0..0x8] != b"\x89PNG\r\n\x1a\n"
+            || &data[0xC..0x10] != b"IHDR" {
+        return None;
+    }
+    Some((
+        BigEndian::read_u32(&data[0x10..0x14]),
+        BigEndian::read_u32(&data[0x14..0x18]),
+    ))
+}
+
+
+/// Reads the image dimensions from the start of frame marker of a JPEG,
+/// by walking the marker segments which precede the image data.
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 0x2 || data[0x0..0x2] != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut pos = 0x2;
+    loop {
+        // markers may be preceded by any number of 0xFF fill bytes
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        while *data.get(pos)? == 0xFF {
+            pos += 1;
+        }
+        let marker = data[pos];
+        pos += 1;
+
+        match marker {
+            // standalone markers have no length
+            0x01 | 0xD0..=0xD8 => continue,
+            // end of image, or start of scan before any frame
+            0xD9 | 0xDA => return None,
+            _ => (),
+        }
+
+        let len = BigEndian::read_u16(data.get(pos..pos + 0x2)?) as usize;
+        match marker {
+            // start of frame markers, excluding DHT, JPG, and DAC
+            0xC0..=0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF => {
+                let frame = data.get(pos + 0x3..pos + 0x7)?;
+                return Some((
+                    BigEndian::read_u16(&frame[0x2..0x4]) as u32,
+                    BigEndian::read_u16(&frame[0x0..0x2]) as u32,
+                ));
+            },
+            _ => pos += len,
+        }
+    }
+}
+
+
+/// Reads the module information from the header of a MOD. The 31
+/// sample layout is identified by the format signature following the
+/// pattern table, which also gives the number of channels.
+fn module_info(data: &[u8]) -> Option<SoundInfo> {
+    let signature = data.get(1080..1084);
+    let channels = match signature {
+        Some(b"M.K.") | Some(b"M!K!") | Some(b"M&K!") | Some(b"N.T.")
+            | Some(b"FLT4") | Some(b"4CHN") => Some(4),
+        Some(b"FLT8") | Some(b"OCTA") | Some(b"CD81") => Some(8),
+        Some(id) if &id[1..4] == b"CHN" => digits(&id[0..1]),
+        Some(id) if &id[2..4] == b"CH" => digits(&id[0..2]),
+        _ => None,
+    };
+
+    // the original layout holds 15 samples, and has no signature
+    let (samples, channels, positions) = match channels {
+        Some(channels) => (31, channels, 950),
+        None => (15, 4, 470),
+    };
+    let table = data.get(positions + 2..positions + 130)?;
+    let num = *data.get(positions)? as u16;
+    if num == 0 || num > 128 {
+        return None;
+    }
+
+    Some(SoundInfo{
+        channels,
+        samples,
+        patterns: *table.iter().max()? as u16 + 1,
+        positions: num,
+    })
+}
+
+
+/// Finds a UUID embedded in an executable as `UUID://...//`, where the
+/// UUID is 36 characters long. The UUID is returned in upper case.
+fn embedded_uuid(code: &[u8]) -> Option<String> {
+    code.windows(0x2D)
+        .find(|window| window.starts_with(b"UUID://")
+            && window.ends_with(b"//")
+            && window[0x7..0x2B].iter()
+                .all(|&b| b.is_ascii_hexdigit() || b == b'-'))
+        .map(|window| String::from_utf8_lossy(&window[0x7..0x2B])
+            .to_uppercase())
+}
+
+
+/// Builds the IFID of a Z-code story file from its header, as
+/// `ZCODE-<release>-<serial>-<checksum>`. Characters of the serial
+/// number which are not alphanumeric are replaced with `-`. The
+/// checksum is only included if the serial number is not `000000`, and
+/// begins with a digit other than `8`.
+fn zcode_ifid(code: &[u8]) -> Option<String> {
+    let header = code.get(0x0..0x40)?;
+    if header[0x0] < 1 || header[0x0] > 8 {
+        return None;
+    }
+    let release = BigEndian::read_u16(&header[0x2..0x4]);
+    let serial: String = header[0x12..0x18].iter()
+        .map(|&b| if b.is_ascii_alphanumeric() {b as char} else {'-'})
+        .collect();
+
+    let mut ifid = format!("ZCODE-{}-{}", release, serial);
+    if serial != "000000"
+            && header[0x12].is_ascii_digit()
+            && header[0x12] != b'8' {
+        let checksum = BigEndian::read_u16(&header[0x1C..0x1E]);
+        ifid.push_str(&format!("-{:04X}", checksum));
+    }
+    Some(ifid)
+}
+
+
+/// Builds the IFID of a Glulx game file compiled by Inform, identified
+/// by the `Info` block following the Glulx header, as
+/// `GLULX-<release>-<serial>-<checksum>`. `None` is returned for
+/// other Glulx game files.
+fn glulx_ifid(code: &[u8]) -> Option<String> {
+    let header = code.get(0x0..0x3C)?;
+    if &header[0x0..0x4] != b"Glul" || &header[0x24..0x28] != b"Info" {
+        return None;
+    }
+    let release = BigEndian::read_u16(&header[0x34..0x36]);
+    let serial: String = header[0x36..0x3C].iter()
+        .map(|&b| if b.is_ascii_alphanumeric() {b as char} else {'-'})
+        .collect();
+    let checksum = BigEndian::read_u32(&header[0x20..0x24]);
+    Some(format!("GLULX-{}-{}-{:08X}", release, serial, checksum))
+}
+
+
+/// Parses ascii digits as a channel count. Returns `None` if the bytes
+/// are not all digits, or give no channels.
+fn digits(bytes: &[u8]) -> Option<u16> {
+    let mut value = 0;
+    for &b in bytes {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        value = value * 10 + (b - b'0') as u16;
+    }
+    if value == 0 {None} else {Some(value)}
+}
+
+
+impl fmt::Debug for Chunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Chunk::Unknown{ref meta, ref data} => f.debug_struct("Unknown")
+                .field("meta", meta)
+                .field("data", &ByteLen(data.len()))
+                .finish(),
+            Chunk::UnknownForm{ref meta, ref data} =>
+                f.debug_struct("UnknownForm")
+                    .field("meta", meta)
+                    .field("data", &ByteLen(data.len()))
+                    .finish(),
+            Chunk::ResourceIndex{ref index} => f.debug_struct("ResourceIndex")
+                .field("index", index)
+                .finish(),
+            Chunk::Metadata{ref info} => f.debug_struct("Metadata")
+                .field("info", info)
+                .finish(),
+            Chunk::Frontispiece{num} => f.debug_struct("Frontispiece")
+                .field("num", &num)
+                .finish(),
+            Chunk::Resolution{px, py, minx, miny, maxx, maxy, ref images} =>
+                f.debug_struct("Resolution")
+                    .field("px", &px)
+                    .field("py", &py)
+                    .field("minx", &minx)
+                    .field("miny", &miny)
+                    .field("maxx", &maxx)
+                    .field("maxy", &maxy)
+                    .field("images", images)
+                    .finish(),
+            Chunk::Rectangle{width, height} => f.debug_struct("Rectangle")
+                .field("width", &width)
+                .field("height", &height)
+                .finish(),
+            Chunk::Text{ref text} => f.debug_struct("Text")
+                .field("text", text)
+                .finish(),
+            Chunk::Author{ref text} => f.debug_struct("Author")
+                .field("text", text)
+                .finish(),
+            Chunk::Copyright{ref text} => f.debug_struct("Copyright")
+                .field("text", text)
+                .finish(),
+            Chunk::Annotation{ref text} => f.debug_struct("Annotation")
+                .field("text", text)
+                .finish(),
+            Chunk::ReleaseNumber{number} => f.debug_struct("ReleaseNumber")
+                .field("number", &number)
+                .finish(),
+            Chunk::AdaptivePalette{ref entries} =>
+                f.debug_struct("AdaptivePalette")
+                    .field("entries", entries)
+                    .finish(),
+            Chunk::Palette{ref colors} => f.debug_struct("Palette")
+                .field("colors", colors)
+                .finish(),
+            Chunk::DirectPalette{depth} => f.debug_struct("DirectPalette")
+                .field("depth", &depth)
+                .finish(),
+            Chunk::Debug{ref data} => debug_bytes(f, "Debug", "data", data),
+            Chunk::ShortName{ref name} => f.debug_struct("ShortName")
+                .field("name", name)
+                .finish(),
+            Chunk::ZCode{ref code} => debug_bytes(f, "ZCode", "code", code),
+            Chunk::Glulx{ref code} => debug_bytes(f, "Glulx", "code", code),
+            Chunk::Tads2{ref code} => debug_bytes(f, "Tads2", "code", code),
+            Chunk::Tads3{ref code} => debug_bytes(f, "Tads3", "code", code),
+            Chunk::Hugo{ref code} => debug_bytes(f, "Hugo", "code", code),
+            Chunk::Alan{ref code} => debug_bytes(f, "Alan", "code", code),
+            Chunk::Adrift{ref code} => debug_bytes(f, "Adrift", "code", code),
+            Chunk::Level9{ref code} => debug_bytes(f, "Level9", "code", code),
+            Chunk::Agt{ref code} => debug_bytes(f, "Agt", "code", code),
+            Chunk::MagneticScrolls{ref code} =>
+                debug_bytes(f, "MagneticScrolls", "code", code),
+            Chunk::AdvSys{ref code} => debug_bytes(f, "AdvSys", "code", code),
+            Chunk::Exec{ref code} => debug_bytes(f, "Exec", "code", code),
+            Chunk::Png{ref data} => debug_bytes(f, "Png", "data", data),
+            Chunk::Jpeg{ref data} => debug_bytes(f, "Jpeg", "data", data),
+            Chunk::Aiff{ref data} => debug_bytes(f, "Aiff", "data", data),
+            Chunk::Ogg{ref data} => debug_bytes(f, "Ogg", "data", data),
+            Chunk::Mod{ref data} => debug_bytes(f, "Mod", "data", data),
+            Chunk::Song{ref data} => debug_bytes(f, "Song", "data", data),
+            Chunk::Binary{ref data} => debug_bytes(f, "Binary", "data", data),
+            Chunk::Gif{ref data} => debug_bytes(f, "Gif", "data", data),
+            Chunk::Wav{ref data} => debug_bytes(f, "Wav", "data", data),
+            Chunk::Midi{ref data} => debug_bytes(f, "Midi", "data", data),
+            Chunk::Mp3{ref data} => debug_bytes(f, "Mp3", "data", data),
+        }
+    }
+}
+
+
+/// Displays a human readable name for the chunk type, such as
+/// `Glulx executable` or `Resource index`. For chunks holding file
+/// data, the length of the data is included, as in
+/// `PNG image (4096 bytes)`.
+impl fmt::Display for Chunk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Chunk::Unknown{..} => "Unknown chunk",
+            Chunk::UnknownForm{..} => "Unknown form",
+            Chunk::ResourceIndex{..} => "Resource index",
+            Chunk::Metadata{..} => "Metadata",
+            Chunk::Frontispiece{..} => "Frontispiece",
+            Chunk::Resolution{..} => "Resolution",
+            Chunk::Author{..} => "Author",
+            Chunk::Copyright{..} => "Copyright",
+            Chunk::Annotation{..} => "Annotation",
+            Chunk::ReleaseNumber{..} => "Release number",
+            Chunk::AdaptivePalette{..} => "Adaptive palette",
+            Chunk::Palette{..} => "Palette",
+            Chunk::DirectPalette{..} => "Direct color palette",
+            Chunk::Debug{..} => "Debug information",
+            Chunk::ShortName{..} => "Short name",
+            Chunk::ZCode{..} => "Z-code executable",
+            Chunk::Glulx{..} => "Glulx executable",
+            Chunk::Tads2{..} => "TADS 2 executable",
+            Chunk::Tads3{..} => "TADS 3 executable",
+            Chunk::Hugo{..} => "Hugo executable",
+            Chunk::Alan{..} => "Alan executable",
+            Chunk::Adrift{..} => "ADRIFT executable",
+            Chunk::Level9{..} => "Level 9 executable",
+            Chunk::Agt{..} => "AGT executable",
+            Chunk::MagneticScrolls{..} => "Magnetic Scrolls executable",
+            Chunk::AdvSys{..} => "AdvSys executable",
+            Chunk::Exec{..} => "Native executable",
+            Chunk::Png{..} => "PNG image",
+            Chunk::Jpeg{..} => "JPEG image",
+            Chunk::Rectangle{..} => "Placeholder image",
+            Chunk::Aiff{..} => "AIFF sound",
+            Chunk::Ogg{..} => "Ogg Vorbis sound",
+            Chunk::Mod{..} => "MOD sound",
+            Chunk::Song{..} => "SONG sound",
+            Chunk::Text{..} => "Text data",
+            Chunk::Binary{..} => "Binary data",
+            Chunk::Gif{..} => "GIF image",
+            Chunk::Wav{..} => "WAV sound",
+            Chunk::Midi{..} => "MIDI sound",
+            Chunk::Mp3{..} => "MP3 sound",
+        };
+        match self.bytes() {
+            Some(bytes) => write!(f, "{} ({} bytes)", name, bytes.len()),
+            None => f.write_str(name),
+        }
+    }
+}
+
+
+/// Debug formats as the number of bytes in a payload.
+struct ByteLen(usize);
+
+
+impl fmt::Debug for ByteLen {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{} bytes>", self.0)
+    }
+}
+
+
+/// Debug formats a chunk variant with a single byte payload field.
+fn debug_bytes(f: &mut fmt::Formatter, name: &str, field: &str, bytes: &[u8])
+        -> fmt::Result {
+    f.debug_struct(name).field(field, &ByteLen(bytes.len())).finish()
+}
+
+
+// Blorb Structs
+////////////////////////////////////////////////////////////////////////
+
+
+/// A blorb loaded fully into memory.
+///
+/// Constructed using `Blorb::load`, which reads every chunk of the
+/// blorb in a single pass, without requiring `std::io::Seek`. This is
+/// convenient for tools and small blorbs; interpreters should prefer
+/// the lazy access of `BlorbCursor`.
+#[derive(Clone, Debug)]
+pub struct Blorb {
+    /// The resource index of the blorb.
+    pub index: ResourceIndex,
+    /// a map of resource number to the chunk of each picture.
+    pub pictures: HashMap<usize, Chunk>,
+    /// a map of resource number to the chunk of each sound.
+    pub sounds: HashMap<usize, Chunk>,
+    /// a map of resource number to the chunk of each data resource.
+    pub data: HashMap<usize, Chunk>,
+    /// the executable chunk, if present.
+    pub exec: Option<Chunk>,
+    /// the xml metadata, from the `IFmd` chunk, if present.
+    pub metadata: Option<String>,
+    /// the picture resource number of the frontispiece, from the `Fspc`
+    /// chunk, if present.
+    pub frontispiece: Option<u32>,
+    /// every other chunk not referenced by the resource index, in file
+    /// order.
+    pub chunks: Vec<Chunk>,
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_bytes_round_trip() {
+        for usage in &[Usage::Pict, Usage::Snd, Usage::Data, Usage::Exec] {
+            assert_eq!(Usage::from_bytes(&usage.as_bytes()), Some(*usage));
+        }
+        assert_eq!(&Usage::Snd.as_bytes(), b"Snd ");
+        assert_eq!(Usage::from_bytes(b"Snd\0"), None);
+    }
+
+    #[test]
+    fn image_dimensions_png() {
+        let png = Chunk::Png{data: b"\x89PNG\r\n\x1a\n\
+            \0\0\0\x0DIHDR\0\0\x02\x80\0\0\x01\xE0\x08\x02\0\0\0".to_vec()};
+        assert_eq!(png.image_dimensions(), Some((640, 480)));
+
+        let truncated = Chunk::Png{
+            data: b"\x89PNG\r\n\x1a\n\0\0\0\x0DIHDR".to_vec(),
+        };
+        assert_eq!(truncated.image_dimensions(), None);
+    }
+
+    #[test]
+    fn image_dimensions_jpeg() {
+        let jpeg = Chunk::Jpeg{data: b"\xFF\xD8\
+            \xFF\xE0\0\x06JFIF\
+            \xFF\xFF\xC2\0\x0B\x08\x00\x20\x00\x40\x01\x01\x11\x00\
+            \xFF\xDA".to_vec()};
+        assert_eq!(jpeg.image_dimensions(), Some((64, 32)));
+
+        let no_frame = Chunk::Jpeg{data: b"\xFF\xD8\xFF\xDA\0\x02".to_vec()};
+        assert_eq!(no_frame.image_dimensions(), None);
+    }
+
+    #[test]
+    fn image_dimensions_other() {
+        let rect = Chunk::Rectangle{width: 3, height: 4};
+        assert_eq!(rect.image_dimensions(), Some((3, 4)));
+        assert_eq!(Chunk::Binary{data: vec![]}.image_dimensions(), None);
+    }
+
+    #[test]
+    fn chunk_debug_shows_length() {
+        let debug = format!("{:?}", Chunk::Png{data: vec![0xAB; 0x10]});
+        assert_eq!(debug, "Png { data: <16 bytes> }");
+        assert!(!debug.contains("171"));
+    }
+
+    #[test]
+    fn file_extension_media() {
+        let cases = vec![
+            (Chunk::Png{data: vec![]}, "png"),
+            (Chunk::Jpeg{data: vec![]}, "jpeg"),
+            (Chunk::Gif{data: vec![]}, "gif"),
+            (Chunk::Aiff{data: vec![]}, "aiff"),
+            (Chunk::Ogg{data: vec![]}, "ogg"),
+            (Chunk::Mod{data: vec![]}, "mod"),
+            (Chunk::Song{data: vec![]}, "song"),
+            (Chunk::Wav{data: vec![]}, "wav"),
+            (Chunk::Midi{data: vec![]}, "mid"),
+            (Chunk::Mp3{data: vec![]}, "mp3"),
+            (Chunk::Text{text: String::new()}, "txt"),
+            (Chunk::Binary{data: vec![]}, "bin"),
+            (Chunk::Glulx{code: vec![]}, "ulx"),
+            (Chunk::ZCode{code: vec![0x5, 0x0]}, "z5"),
+            (Chunk::ZCode{code: vec![]}, "zcode"),
+        ];
+        for (chunk, extension) in cases {
+            assert_eq!(chunk.file_extension(), Some(extension));
+        }
+        let rect = Chunk::Rectangle{width: 1, height: 1};
+        assert_eq!(rect.file_extension(), None);
+        assert_eq!(Chunk::Frontispiece{num: 1}.file_extension(), None);
+    }
+
+    #[test]
+    fn bytes_payload() {
+        let cases = [
+            Chunk::Png{data: b"png".to_vec()},
+            Chunk::Ogg{data: b"ogg".to_vec()},
+            Chunk::Wav{data: b"wav".to_vec()},
+            Chunk::Binary{data: b"bin".to_vec()},
+            Chunk::ZCode{code: b"zcode".to_vec()},
+            Chunk::Glulx{code: b"glulx".to_vec()},
+            Chunk::Text{text: "text".to_string()},
+        ];
+        let bytes: Vec<_> = cases.iter().map(|chunk| chunk.bytes()).collect();
+        assert_eq!(bytes, vec![
+            Some(&b"png"[..]), Some(&b"ogg"[..]), Some(&b"wav"[..]),
+            Some(&b"bin"[..]), Some(&b"zcode"[..]), Some(&b"glulx"[..]),
+            Some(&b"text"[..]),
+        ]);
+
+        assert_eq!(Chunk::Frontispiece{num: 1}.bytes(), None);
+        assert_eq!(Chunk::Rectangle{width: 1, height: 1}.bytes(), None);
+    }
+
+    #[test]
+    fn chunk_equality() {
+        let data = b"\x89PNG\r\n\x1a\n".to_vec();
+        let first = Chunk::Png{data: data.clone()};
+        let second = Chunk::Png{data};
+        assert_eq!(first, second);
+        assert!(first != Chunk::Jpeg{data: first.bytes().unwrap().to_vec()});
+        assert!(first != Chunk::Png{data: vec![]});
+    }
+
+    /// Builds a 31 sample MOD header with the given signature, playing
+    /// the given pattern positions.
+    fn module(signature: &[u8; 0x4], positions: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x0; 1084];
+        data[950] = positions.len() as u8;
+        data[952..952 + positions.len()].copy_from_slice(positions);
+        data[1080..1084].copy_from_slice(signature);
+        data
+    }
+
+    #[test]
+    fn sound_info_mod() {
+        let chunk = Chunk::Mod{data: module(b"M.K.", &[0, 2, 1, 2])};
+        assert_eq!(chunk.sound_info(), Some(SoundInfo{
+            channels: 4,
+            samples: 31,
+            patterns: 3,
+            positions: 4,
+        }));
+
+        let chunk = Chunk::Song{data: module(b"12CH", &[0])};
+        let info = chunk.sound_info().unwrap();
+        assert_eq!((info.channels, info.patterns), (12, 1));
+
+        let mut data = vec![0x0; 600];
+        data[470] = 1;
+        data[472] = 5;
+        let info = Chunk::Mod{data}.sound_info().unwrap();
+        assert_eq!((info.channels, info.samples, info.patterns), (4, 15, 6));
+    }
+
+    #[test]
+    fn sound_info_other() {
+        assert_eq!(Chunk::Mod{data: vec![0x0; 100]}.sound_info(), None);
+        assert_eq!(Chunk::Ogg{data: module(b"M.K.", &[0])}.sound_info(), None);
+    }
+
+    #[test]
+    fn resource_index_insert_and_get() {
+        let mut index = ResourceIndex::new();
+        let usages = [Usage::Pict, Usage::Snd, Usage::Data, Usage::Exec];
+        for (i, &usage) in usages.iter().enumerate() {
+            let num = i as u32 + 1;
+            index.insert(IndexEntry{usage, num, start: num * 0x10});
+        }
+
+        for (i, &usage) in usages.iter().enumerate() {
+            let num = i as u32 + 1;
+            let entry = index.get(usage, num).unwrap();
+            assert_eq!(entry, &IndexEntry{
+                usage,
+                num,
+                start: num * 0x10,
+            });
+        }
+        assert!(index.get(Usage::Pict, 2).is_none());
+        assert!(index.get(Usage::Snd, 1).is_none());
+        assert_eq!(index.entries().count(), 4);
+
+        // a second entry with the same usage and number replaces the first
+        index.insert(IndexEntry{usage: Usage::Pict, num: 1, start: 0x80});
+        assert_eq!(index.get(Usage::Pict, 1).unwrap().start, 0x80);
+        assert_eq!(index.entries().count(), 4);
+    }
+
+    #[test]
+    fn display_names_chunk() {
+        let png = Chunk::Png{data: vec![0x0; 4096]};
+        assert_eq!(png.to_string(), "PNG image (4096 bytes)");
+
+        let index = Chunk::ResourceIndex{index: ResourceIndex::new()};
+        assert_eq!(index.to_string(), "Resource index");
+    }
+
+    #[test]
+    fn ifid_from_executable() {
+        let mut story = vec![0x0; 0x40];
+        story[0x0] = 3;
+        story[0x3] = 88;
+        story[0x12..0x18].copy_from_slice(b"840726");
+        story[0x1C..0x1E].copy_from_slice(&[0x12, 0x34]);
+        let zcode = Chunk::ZCode{code: story.clone()};
+        assert_eq!(zcode.ifid(), Some("ZCODE-88-840726".to_string()));
+
+        story.extend_from_slice(
+            b"UUID://1974a053-7dd4-4a45-b48c-c9c0ef6f8c27//");
+        let zcode = Chunk::ZCode{code: story};
+        assert_eq!(
+            zcode.ifid(),
+            Some("1974A053-7DD4-4A45-B48C-C9C0EF6F8C27".to_string()));
+
+        let mut game = vec![0x0; 0x3C];
+        game[0x0..0x4].copy_from_slice(b"Glul");
+        game[0x20..0x24].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        game[0x24..0x28].copy_from_slice(b"Info");
+        game[0x35] = 2;
+        game[0x36..0x3C].copy_from_slice(b"100101");
+        let glulx = Chunk::Glulx{code: game};
+        assert_eq!(
+            glulx.ifid(),
+            Some("GLULX-2-100101-DEADBEEF".to_string()));
+
+        assert_eq!(Chunk::Png{data: Vec::new()}.ifid(), None);
+    }
+}
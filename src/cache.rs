@@ -0,0 +1,216 @@
+use std::collections::{
+    HashMap,
+    VecDeque,
+};
+use std::io::{
+    Read,
+    Seek,
+};
+use std::rc::Rc;
+
+use crate::blorb::{
+    Chunk,
+    Usage,
+};
+use crate::error::Result;
+use crate::io::BlorbCursor;
+
+
+/// Wraps a `BlorbCursor`, caching loaded resources in memory.
+///
+/// Resources loaded through `CachedBlorbCursor::load_resource` are kept
+/// in the cache, so loading the same resource again does not access
+/// the underlying file. At most `cache_limit` resources are kept; when
+/// the cache is full, the least recently used resource is evicted.
+pub struct CachedBlorbCursor<R: Read + Seek> {
+    cursor: BlorbCursor<R>,
+    cache: HashMap<(Usage, u32), Rc<Chunk>>,
+    order: VecDeque<(Usage, u32)>,
+    cache_limit: usize,
+    hits: u64,
+    misses: u64,
+}
+
+
+impl<R: Read + Seek> CachedBlorbCursor<R> {
+
+    /// Returns a `CachedBlorbCursor` wrapping the given `BlorbCursor`,
+    /// which caches at most `cache_limit` resources. A `cache_limit` of
+    /// 0 disables caching.
+    pub fn new(cursor: BlorbCursor<R>, cache_limit: usize)
+            -> CachedBlorbCursor<R> {
+        CachedBlorbCursor{
+            cursor,
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            cache_limit,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Loads the resource with the given usage and index, as with
+    /// `BlorbCursor::load_resource`, returning the cached chunk if it
+    /// has been loaded before. A `BlorbError` is returned if the
+    /// resource is not cached and fails to load.
+    pub fn load_resource(&mut self, usage: Usage, index: u32)
+            -> Result<Rc<Chunk>> {
+        let key = (usage, index);
+        if let Some(chunk) = self.cache.get(&key).cloned() {
+            self.hits += 1;
+            self.touch(key);
+            return Ok(chunk);
+        }
+
+        self.misses += 1;
+        let chunk = Rc::new(self.cursor.load_resource(usage, index)?);
+        if self.cache_limit > 0 {
+            if self.cache.len() >= self.cache_limit {
+                if let Some(evicted) = self.order.pop_front() {
+                    self.cache.remove(&evicted);
+                }
+            }
+            self.cache.insert(key, chunk.clone());
+            self.order.push_back(key);
+        }
+        Ok(chunk)
+    }
+
+    /// Returns the maximum number of resources kept in the cache.
+    pub fn cache_limit(&self) -> usize {
+        self.cache_limit
+    }
+
+    /// Sets the maximum number of resources kept in the cache, evicting
+    /// the least recently used resources over the new limit.
+    pub fn set_cache_limit(&mut self, cache_limit: usize) {
+        self.cache_limit = cache_limit;
+        while self.cache.len() > cache_limit {
+            if let Some(evicted) = self.order.pop_front() {
+                self.cache.remove(&evicted);
+            }
+        }
+    }
+
+    /// Returns the number of loads which were served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Returns the number of loads which accessed the underlying file.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Removes every resource from the cache.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.order.clear();
+    }
+
+    /// Returns a mutable reference to the wrapped `BlorbCursor`, for
+    /// uncached access to the blorb.
+    pub fn get_mut(&mut self) -> &mut BlorbCursor<R> {
+        &mut self.cursor
+    }
+
+    /// Unwraps the `CachedBlorbCursor`, returning the wrapped
+    /// `BlorbCursor`.
+    pub fn into_inner(self) -> BlorbCursor<R> {
+        self.cursor
+    }
+
+    /// Marks the cached resource with the given key as the most
+    /// recently used.
+    fn touch(&mut self, key: (Usage, u32)) {
+        if let Some(pos) = self.order.iter().position(|&k| k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::io;
+    use std::io::{
+        Cursor,
+        SeekFrom,
+    };
+
+    use crate::testutil::build_blorb;
+    use super::*;
+
+    /// Counts the seeks made on the wrapped reader.
+    struct SeekCounter {
+        inner: Cursor<Vec<u8>>,
+        seeks: Rc<Cell<usize>>,
+    }
+
+    impl Read for SeekCounter {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for SeekCounter {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.seeks.set(self.seeks.get() + 1);
+            self.inner.seek(pos)
+        }
+    }
+
+    fn open(cache_limit: usize)
+            -> (CachedBlorbCursor<SeekCounter>, Rc<Cell<usize>>) {
+        let blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Pict", 2, b"JPEG", b"jpeg"),
+            (b"Snd ", 1, b"OGGV", b"ogg"),
+        ], &[]);
+        let seeks = Rc::new(Cell::new(0));
+        let src = SeekCounter{inner: Cursor::new(blorb), seeks: seeks.clone()};
+        let cursor = BlorbCursor::from_file(src).unwrap();
+        (CachedBlorbCursor::new(cursor, cache_limit), seeks)
+    }
+
+    #[test]
+    fn cached_load_does_not_seek() {
+        let (mut cursor, seeks) = open(2);
+
+        let first = cursor.load_resource(Usage::Pict, 1).unwrap();
+        let after_first = seeks.get();
+        let second = cursor.load_resource(Usage::Pict, 1).unwrap();
+
+        assert_eq!(seeks.get(), after_first);
+        assert!(Rc::ptr_eq(&first, &second));
+        assert_eq!((cursor.hits(), cursor.misses()), (1, 1));
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let (mut cursor, seeks) = open(2);
+
+        cursor.load_resource(Usage::Pict, 1).unwrap();
+        cursor.load_resource(Usage::Pict, 2).unwrap();
+        cursor.load_resource(Usage::Pict, 1).unwrap();
+        cursor.load_resource(Usage::Snd, 1).unwrap();
+
+        // picture 2 was least recently used, so was evicted
+        let before = seeks.get();
+        cursor.load_resource(Usage::Pict, 1).unwrap();
+        assert_eq!(seeks.get(), before);
+        cursor.load_resource(Usage::Pict, 2).unwrap();
+        assert!(seeks.get() > before);
+        assert_eq!((cursor.hits(), cursor.misses()), (2, 4));
+    }
+
+    #[test]
+    fn zero_limit_disables_cache() {
+        let (mut cursor, _) = open(0);
+        cursor.load_resource(Usage::Pict, 1).unwrap();
+        cursor.load_resource(Usage::Pict, 1).unwrap();
+        assert_eq!((cursor.hits(), cursor.misses()), (0, 2));
+    }
+}
@@ -0,0 +1,415 @@
+use std::fmt::Display;
+use std::io::{
+    Cursor,
+    Read,
+    Seek,
+};
+
+use byteorder::{
+    BigEndian,
+    ByteOrder,
+};
+use lewton::inside_ogg::OggStreamReader;
+
+use crate::blorb::{
+    Chunk,
+    Usage,
+};
+use crate::error::{
+    BlorbError,
+    Result,
+};
+use crate::io::BlorbCursor;
+
+
+/// Decoded sound data, as interleaved signed 16 bit PCM samples.
+///
+/// Samples are interleaved by channel, so a stereo sound holds the
+/// left sample of a frame followed by the right sample of the frame.
+#[derive(Debug)]
+pub struct PcmData {
+    /// The number of interleaved channels in `samples`.
+    pub channels: u16,
+    /// The number of frames per second.
+    pub sample_rate: u32,
+    /// The interleaved samples of the sound.
+    pub samples: Vec<i16>,
+}
+
+
+impl<R: Read + Seek> BlorbCursor<R> {
+
+    /// Loads the sound resource with the given index, and decodes it
+    /// into `PcmData`. AIFF, WAV, OGG, and MP3 sounds are supported.
+    /// MOD, SONG, and MIDI sounds are sequenced rather than sampled,
+    /// and return a `BlorbError::Decode` indicating a sequencer is
+    /// needed. A `BlorbError` is also returned if the resource cannot
+    /// be loaded, or if the sound data fails to decode.
+    pub fn load_sound_pcm(&mut self, num: u32) -> Result<PcmData> {
+        match self.load_resource(Usage::Snd, num)? {
+            Chunk::Aiff{data} => decode_aiff(&data),
+            Chunk::Wav{data} => decode_wav(data),
+            Chunk::Ogg{data} => decode_ogg(data),
+            Chunk::Mp3{data} => decode_mp3(data),
+            Chunk::Mod{..} | Chunk::Song{..} | Chunk::Midi{..} =>
+                Err(decode_error("sound resource requires a sequencer")),
+            _ => Err(decode_error("resource is not a decodable sound")),
+        }
+    }
+}
+
+
+/// Wraps a decoding failure, such as an error from one of the decoding
+/// libraries, into a `BlorbError::Decode`.
+fn decode_error<E: Display>(err: E) -> BlorbError {
+    BlorbError::Decode(err.to_string())
+}
+
+
+/// Converts an 80 bit IEEE 754 extended precision float, as used by the
+/// AIFF `COMM` chunk sample rate, into a whole number of samples.
+fn extended_to_u32(bytes: &[u8]) -> u32 {
+    let exponent = (BigEndian::read_u16(&bytes[0x0..0x2]) & 0x7FFF) as i32;
+    let mantissa = (BigEndian::read_u32(&bytes[0x2..0x6]) as u64) << 32
+        | BigEndian::read_u32(&bytes[0x6..0xA]) as u64;
+    let shift = 16383 + 63 - exponent;
+    if !(0..64).contains(&shift) {
+        0
+    } else {
+        (mantissa >> shift) as u32
+    }
+}
+
+
+/// Decodes a full `FORM` wrapped AIFF sound. Only uncompressed AIFF is
+/// supported, using the `COMM` chunk for the sound format and the
+/// `SSND` chunk for the samples.
+fn decode_aiff(data: &[u8]) -> Result<PcmData> {
+    if data.len() < 0xC || &data[0x8..0xC] != b"AIFF" {
+        return Err(decode_error("sound is not AIFF"));
+    }
+
+    let mut format = None;
+    let mut sound = None;
+    let mut pos = 0xC;
+    while pos + 0x8 <= data.len() {
+        let len = BigEndian::read_u32(&data[pos + 0x4..pos + 0x8]) as usize;
+        let body = pos + 0x8;
+
+        // the chunk bounds are checked, as the additions may overflow on
+        // targets with a 32 bit usize.
+        let end = match body.checked_add(len) {
+            Some(end) if end <= data.len() => end,
+            _ => return Err(decode_error(
+                "AIFF chunk extends past end of sound")),
+        };
+        match &data[pos..pos + 0x4] {
+            b"COMM" if len >= 18 => format = Some((
+                BigEndian::read_u16(&data[body..body + 0x2]),
+                BigEndian::read_u16(&data[body + 0x6..body + 0x8]),
+                extended_to_u32(&data[body + 0x8..body + 0x12]),
+            )),
+            b"SSND" if len >= 8 => {
+                let offset = BigEndian::read_u32(&data[body..body + 0x4]);
+                let start = match (body + 0x8).checked_add(offset as usize) {
+                    Some(start) if start <= end => start,
+                    _ => return Err(decode_error(
+                        "AIFF sound data offset out of range")),
+                };
+                sound = Some(&data[start..end]);
+            },
+            _ => (),
+        }
+        pos = match end.checked_add(len & 1) {
+            Some(pos) => pos,
+            None => return Err(decode_error(
+                "AIFF chunk extends past end of sound")),
+        };
+    }
+
+    let (channels, bits, sample_rate) = match format {
+        Some(format) => format,
+        None => return Err(decode_error("AIFF missing COMM chunk")),
+    };
+    let sound = sound.unwrap_or(&[]);
+
+    // AIFF samples are big endian, and padded up to whole bytes.
+    let width = (bits as usize + 7) / 8;
+    if width == 0 || width > 4 {
+        return Err(decode_error("unsupported AIFF sample size"));
+    }
+    let samples = sound.chunks(width)
+        .filter(|sample| sample.len() == width)
+        .map(|sample| match width {
+            1 => (sample[0] as i8 as i16) << 8,
+            _ => BigEndian::read_i16(&sample[0x0..0x2]),
+        })
+        .collect();
+
+    Ok(PcmData{channels, sample_rate, samples})
+}
+
+
+/// Decodes a WAV sound, converting the samples to 16 bit.
+fn decode_wav(data: Vec<u8>) -> Result<PcmData> {
+    let mut reader = hound::WavReader::new(Cursor::new(data))
+        .map_err(decode_error)?;
+    let spec = reader.spec();
+
+    let samples = match (spec.sample_format, spec.bits_per_sample) {
+        (hound::SampleFormat::Float, _) => reader.samples::<f32>()
+            .map(|s| s.map(|s| (s * i16::MAX as f32) as i16))
+            .collect::<::std::result::Result<Vec<_>, _>>(),
+        (hound::SampleFormat::Int, bits) if bits > 16 => reader.samples::<i32>()
+            .map(|s| s.map(|s| (s >> (bits - 16)) as i16))
+            .collect(),
+        (hound::SampleFormat::Int, bits) if bits <= 8 => reader.samples::<i8>()
+            .map(|s| s.map(|s| (s as i16) << 8))
+            .collect(),
+        (hound::SampleFormat::Int, _) => reader.samples::<i16>().collect(),
+    }.map_err(decode_error)?;
+
+    Ok(PcmData{
+        channels: spec.channels,
+        sample_rate: spec.sample_rate,
+        samples,
+    })
+}
+
+
+/// Decodes an OGG Vorbis sound.
+fn decode_ogg(data: Vec<u8>) -> Result<PcmData> {
+    let mut reader = OggStreamReader::new(Cursor::new(data))
+        .map_err(decode_error)?;
+    let channels = reader.ident_hdr.audio_channels as u16;
+    let sample_rate = reader.ident_hdr.audio_sample_rate;
+
+    let mut samples = Vec::new();
+    while let Some(packet) = reader.read_dec_packet_itl()
+            .map_err(decode_error)? {
+        samples.extend(packet);
+    }
+
+    Ok(PcmData{channels, sample_rate, samples})
+}
+
+
+/// Decodes an MP3 sound. The channel count and sample rate are taken
+/// from the first frame of the sound.
+fn decode_mp3(data: Vec<u8>) -> Result<PcmData> {
+    let mut decoder = minimp3::Decoder::new(Cursor::new(data));
+    let mut pcm = PcmData{channels: 0, sample_rate: 0, samples: Vec::new()};
+    loop {
+        match decoder.next_frame() {
+            Ok(frame) => {
+                if pcm.channels == 0 {
+                    pcm.channels = frame.channels as u16;
+                    pcm.sample_rate = frame.sample_rate as u32;
+                }
+                pcm.samples.extend(frame.data);
+            },
+            Err(minimp3::Error::Eof) => break,
+            Err(err) => return Err(decode_error(err)),
+        }
+    }
+    Ok(pcm)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use byteorder::LittleEndian;
+
+    use crate::testutil::build_blorb;
+    use super::*;
+
+    /// A mono 8000 Hz OGG Vorbis stream of four silent 256 sample
+    /// blocks. The first block only primes the overlap, so the stream
+    /// decodes to 384 samples. The setup header declares a single floor
+    /// and residue with no partitions, and every audio packet marks its
+    /// floor as unused.
+    const OGG_SILENCE: &[u8] = &[
+        0x4F, 0x67, 0x67, 0x53, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x5F, 0x99,
+        0x08, 0x1C, 0x01, 0x1E, 0x01, 0x76, 0x6F, 0x72, 0x62, 0x69, 0x73, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x40, 0x1F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x88, 0x01, 0x4F, 0x67,
+        0x67, 0x53, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x92, 0x85, 0xFD, 0x3E,
+        0x02, 0x10, 0x34, 0x03, 0x76, 0x6F, 0x72, 0x62, 0x69, 0x73, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x05, 0x76, 0x6F, 0x72, 0x62,
+        0x69, 0x73, 0x00, 0x42, 0x43, 0x56, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x4F,
+        0x67, 0x67, 0x53, 0x00, 0x04, 0x80, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x06, 0x14, 0x55,
+        0x6B, 0x04, 0x01, 0x01, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    /// Builds a 16 bit PCM WAV sound with the given samples.
+    fn wav(channels: u16, sample_rate: u32, samples: &[i16]) -> Vec<u8> {
+        let len = samples.len() as u32 * 2;
+        let mut data = vec![0x0; 0x2C + len as usize];
+        data[0x0..0x4].copy_from_slice(b"RIFF");
+        LittleEndian::write_u32(&mut data[0x4..0x8], 0x24 + len);
+        data[0x8..0x10].copy_from_slice(b"WAVEfmt ");
+        LittleEndian::write_u32(&mut data[0x10..0x14], 16);
+        LittleEndian::write_u16(&mut data[0x14..0x16], 1);
+        LittleEndian::write_u16(&mut data[0x16..0x18], channels);
+        LittleEndian::write_u32(&mut data[0x18..0x1C], sample_rate);
+        LittleEndian::write_u32(&mut data[0x1C..0x20],
+            sample_rate * channels as u32 * 2);
+        LittleEndian::write_u16(&mut data[0x20..0x22], channels * 2);
+        LittleEndian::write_u16(&mut data[0x22..0x24], 16);
+        data[0x24..0x28].copy_from_slice(b"data");
+        LittleEndian::write_u32(&mut data[0x28..0x2C], len);
+        for (i, &sample) in samples.iter().enumerate() {
+            let pos = 0x2C + i * 2;
+            LittleEndian::write_i16(&mut data[pos..pos + 2], sample);
+        }
+        data
+    }
+
+    /// Builds silent MPEG-1 layer III frames, mono at 44100 Hz and 128
+    /// kbit/s. With empty side information, each 417 byte frame decodes
+    /// to 1152 zero samples.
+    fn mp3_silence(frames: usize) -> Vec<u8> {
+        let mut frame = vec![0x0; 417];
+        frame[0x0..0x4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0xC0]);
+        frame.repeat(frames)
+    }
+
+    /// Builds an AIFF sound with the given samples, reconstructed with
+    /// its `FORM` envelope as returned by `Chunk::Aiff`.
+    fn aiff(channels: u16, samples: &[i16]) -> Vec<u8> {
+        let mut comm = vec![0x0; 18];
+        BigEndian::write_u16(&mut comm[0x0..0x2], channels);
+        BigEndian::write_u32(&mut comm[0x2..0x6],
+            samples.len() as u32 / channels as u32);
+        BigEndian::write_u16(&mut comm[0x6..0x8], 16);
+        // 22050 as an 80 bit extended float
+        comm[0x8..0x12].copy_from_slice(
+            &[0x40, 0x0D, 0xAC, 0x44, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0]);
+
+        let mut ssnd = vec![0x0; 8 + samples.len() * 2];
+        for (i, sample) in samples.iter().enumerate() {
+            BigEndian::write_i16(&mut ssnd[8 + i * 2..10 + i * 2], *sample);
+        }
+
+        let mut data = b"FORM\0\0\0\0AIFF".to_vec();
+        for &(id, ref body) in &[(b"COMM", comm), (b"SSND", ssnd)] {
+            data.extend_from_slice(id);
+            let mut len = [0x0; 4];
+            BigEndian::write_u32(&mut len, body.len() as u32);
+            data.extend_from_slice(&len);
+            data.extend_from_slice(body);
+        }
+        let len = data.len() as u32 - 8;
+        BigEndian::write_u32(&mut data[0x4..0x8], len);
+        data
+    }
+
+    #[test]
+    fn decode_aiff_stereo() {
+        let pcm = decode_aiff(&aiff(2, &[1, -1, 300, -300])).unwrap();
+        assert_eq!(pcm.channels, 2);
+        assert_eq!(pcm.sample_rate, 22050);
+        assert_eq!(pcm.samples, vec![1, -1, 300, -300]);
+    }
+
+    #[test]
+    fn decode_aiff_missing_comm() {
+        match decode_aiff(b"FORM\0\0\0\x04AIFF") {
+            Err(BlorbError::Decode(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_aiff_oversized_lengths() {
+        // the SSND chunk length
+        let mut data = aiff(1, &[1]);
+        let len = data.len();
+        BigEndian::write_u32(&mut data[len - 0xE..len - 0xA], u32::MAX);
+        match decode_aiff(&data) {
+            Err(BlorbError::Decode(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // the SSND sound data offset
+        let mut data = aiff(1, &[1]);
+        let len = data.len();
+        BigEndian::write_u32(&mut data[len - 0xA..len - 0x6], u32::MAX);
+        match decode_aiff(&data) {
+            Err(BlorbError::Decode(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_wav_stereo() {
+        let pcm = decode_wav(wav(2, 11025, &[1, -1, 300, -300])).unwrap();
+        assert_eq!(pcm.channels, 2);
+        assert_eq!(pcm.sample_rate, 11025);
+        assert_eq!(pcm.samples, vec![1, -1, 300, -300]);
+    }
+
+    #[test]
+    fn decode_wav_invalid() {
+        match decode_wav(b"RIFF\0\0\0\0AIFF".to_vec()) {
+            Err(BlorbError::Decode(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_ogg_silence() {
+        let pcm = decode_ogg(OGG_SILENCE.to_vec()).unwrap();
+        assert_eq!(pcm.channels, 1);
+        assert_eq!(pcm.sample_rate, 8000);
+        assert_eq!(pcm.samples, vec![0; 384]);
+    }
+
+    #[test]
+    fn decode_ogg_invalid() {
+        match decode_ogg(b"OggS".to_vec()) {
+            Err(BlorbError::Decode(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn decode_mp3_silence() {
+        let pcm = decode_mp3(mp3_silence(3)).unwrap();
+        assert_eq!(pcm.channels, 1);
+        assert_eq!(pcm.sample_rate, 44100);
+        assert_eq!(pcm.samples, vec![0; 3 * 1152]);
+    }
+
+    #[test]
+    fn load_sound_pcm_wav() {
+        let data = wav(1, 8000, &[0x100, -0x100]);
+        let blorb = build_blorb(&[(b"Snd ", 1, b"WAV ", &data[..])], &[]);
+        let mut cursor = BlorbCursor::from_bytes(blorb).unwrap();
+        let pcm = cursor.load_sound_pcm(1).unwrap();
+        assert_eq!(pcm.samples, vec![0x100, -0x100]);
+    }
+
+    #[test]
+    fn load_sound_pcm_sequenced() {
+        let blorb = build_blorb(&[
+            (b"Snd ", 1, b"MOD ", b"mod"),
+            (b"Snd ", 2, b"SONG", b"song"),
+            (b"Snd ", 3, b"MIDI", b"midi"),
+        ], &[]);
+        let mut cursor = BlorbCursor::from_bytes(blorb).unwrap();
+        for num in 1..4 {
+            match cursor.load_sound_pcm(num) {
+                Err(BlorbError::Decode(ref msg))
+                    if msg.contains("sequencer") => (),
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::result;
+
+use crate::blorb::Usage;
+
+
+/// Result type returned when reading blorbs.
+pub type Result<T> = result::Result<T, BlorbError>;
+
+
+/// Errors raised when reading a blorb.
+///
+/// Each variant identifies a specific problem with the blorb, so
+/// callers can handle or report them individually. Errors raised by
+/// the underlying source are wrapped in `BlorbError::Io`.
+#[derive(Debug)]
+pub enum BlorbError {
+
+    /// The source does not contain a `FORM` of type `IFRS`.
+    NotBlorb,
+
+    /// The first chunk in the blorb is not a resource index.
+    MissingIndex,
+
+    /// A chunk's declared length does not match the length required by
+    /// its contents. `expected` contains the length the contents
+    /// require, and `found` contains the declared length.
+    BadLength{expected: u32, found: u32},
+
+    /// The resource index chunk's declared length does not match the
+    /// number of entries it declares. `num` contains the declared
+    /// number of entries, `expected` contains the length those entries
+    /// require, and `found` contains the declared length.
+    BadIndexLength{num: u32, expected: u64, found: u32},
+
+    /// A resource index entry has an unrecognized usage identifier.
+    UnknownUsage{id: [u8; 0x4]},
+
+    /// A requested resource is not identified in the resource index.
+    NotFound,
+
+    /// A resource index entry does not point at the start of a chunk
+    /// lying within the blorb. Contains the usage and resource number
+    /// of the entry, and the starting address it points to.
+    BadOffset{usage: Usage, num: u32, start: u32},
+
+    /// The source ended before the 12 byte `FORM` header could be
+    /// read. `available` contains the number of bytes the source
+    /// actually held from the starting position.
+    TooShortForBlorb{available: u64},
+
+    /// The `Exec` resource index entry points at a chunk which is not a
+    /// known executable chunk. Contains the identifier of the chunk.
+    NotExecutable{id: [u8; 0x4]},
+
+    /// The length declared by the blorb's `FORM` header does not match
+    /// the length of the source. `declared` contains the length of the
+    /// form including its 8 byte chunk header, and `actual` contains
+    /// the length of the source.
+    BadFormLength{declared: u64, actual: u64},
+
+    /// Two entries of the resource index share the same usage and
+    /// resource number. Only reported when opening a blorb with
+    /// `BlorbOptions::reject_duplicate_entries` set.
+    DuplicateEntry{usage: Usage, num: u32},
+
+    /// A one byte palette chunk holds a color depth other than the 16
+    /// or 32 bits allowed for direct color. Contains the depth found.
+    BadPaletteDepth{depth: u8},
+
+    /// The metadata of a blorb could not be parsed. Contains a
+    /// description of the problem.
+    BadMetadata(String),
+
+    /// The IFID of a blorb could not be verified, as the executable or
+    /// the IFID in the metadata is missing, or no IFID can be derived
+    /// from the executable. Contains a description of the problem.
+    IfidUnavailable(String),
+
+    /// A sound resource could not be decoded. Contains a description
+    /// of the problem.
+    Decode(String),
+
+    /// An error occurred while accessing the underlying source.
+    Io(io::Error),
+}
+
+
+impl fmt::Display for BlorbError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BlorbError::NotBlorb => write!(f, "file is not blorb"),
+            BlorbError::MissingIndex => write!(f,
+                "blorb missing resource index"),
+            BlorbError::BadLength{expected, found} => write!(f,
+                "chunk length {} does not match expected length {}",
+                found, expected),
+            BlorbError::BadIndexLength{num, expected, found} => write!(f,
+                "resource index length {} does not match expected length \
+                {} for {} entries", found, expected, num),
+            BlorbError::UnknownUsage{ref id} => write!(f,
+                "could not identify index entry usage {:?}",
+                String::from_utf8_lossy(id)),
+            BlorbError::NotFound => write!(f,
+                "no entry associated with the given index"),
+            BlorbError::BadOffset{ref usage, num, start} => write!(f,
+                "{:?} resource {} points to {}, which is not the start of \
+                a chunk within the blorb", usage, num, start),
+            BlorbError::TooShortForBlorb{available} => write!(f,
+                "source too short for blorb: 12 byte header required, \
+                {} bytes available", available),
+            BlorbError::NotExecutable{ref id} => write!(f,
+                "exec resource points to {:?} chunk, which is not an \
+                executable", String::from_utf8_lossy(id)),
+            BlorbError::BadFormLength{declared, actual} => write!(f,
+                "form declares {} bytes, but the source holds {} bytes",
+                declared, actual),
+            BlorbError::DuplicateEntry{ref usage, num} => write!(f,
+                "resource index holds more than one entry for {:?} \
+                resource {}", usage, num),
+            BlorbError::BadPaletteDepth{depth} => write!(f,
+                "palette declares direct color depth {}, expected 16 or 32",
+                depth),
+            BlorbError::BadMetadata(ref msg) => write!(f,
+                "could not parse metadata: {}", msg),
+            BlorbError::IfidUnavailable(ref msg) => write!(f,
+                "could not verify IFID: {}", msg),
+            BlorbError::Decode(ref msg) => write!(f,
+                "could not decode sound: {}", msg),
+            BlorbError::Io(ref err) => err.fmt(f),
+        }
+    }
+}
+
+
+impl error::Error for BlorbError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            BlorbError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+
+impl From<io::Error> for BlorbError {
+    fn from(err: io::Error) -> BlorbError {
+        BlorbError::Io(err)
+    }
+}
+
+
+impl From<BlorbError> for io::Error {
+    fn from(err: BlorbError) -> io::Error {
+        let kind = match err {
+            BlorbError::Io(err) => return err,
+            BlorbError::NotFound => io::ErrorKind::NotFound,
+            BlorbError::TooShortForBlorb{..} => io::ErrorKind::UnexpectedEof,
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, err)
+    }
+}
@@ -1,11 +1,20 @@
 use std::collections::HashMap;
+use std::fs;
+use std::fs::File;
+use std::io;
 use std::io::{
+    Cursor,
     Error,
     ErrorKind,
     Read,
-    Result,
     Seek,
-    SeekFrom
+    SeekFrom,
+    Take,
+    Write,
+};
+use std::path::{
+    Path,
+    PathBuf,
 };
 
 use byteorder::{
@@ -14,14 +23,24 @@ use byteorder::{
     ReadBytesExt,
 };
 
-use blorb::{
+use crate::blorb::{
+    Blorb,
     Chunk,
     ChunkData,
+    ExecKind,
     FormData,
+    ImageFormat,
+    ImageResolution,
     IndexEntry,
     ResourceIndex,
     Usage,
+    id_file_extension,
+};
+use crate::error::{
+    BlorbError,
+    Result,
 };
+use crate::metadata::IfMetadata;
 
 
 /// Provides access to blorb file contents without loading the full file
@@ -41,76 +60,866 @@ pub struct BlorbCursor<R: Read + Seek + ?Sized> {
     /// The length of the blorb, minus the 8 byte chunk header.
     pub len: u32,
     index: ResourceIndex,
+    base: u64,
+    options: BlorbOptions,
     file: R,
 }
 
 
+/// Options for opening a blorb with `BlorbCursor::from_file_with_options`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlorbOptions {
+    /// Skip bytes preceding the `FORM` header, such as a UTF-8 byte
+    /// order mark or junk prepended by a download. The first
+    /// `MAX_LEADING_JUNK` bytes of the source are searched for the
+    /// header of a `FORM` of type `IFRS`.
+    pub skip_leading_junk: bool,
+
+    /// Reject a resource index holding more than one entry with the
+    /// same usage and resource number, returning a
+    /// `BlorbError::DuplicateEntry`. By default, the last such entry is
+    /// used. As a blorb holds at most one executable, a second `Exec`
+    /// entry is always rejected, whatever its resource number.
+    pub reject_duplicate_entries: bool,
+}
+
+
+/// The number of leading bytes searched for the `FORM` header when
+/// `BlorbOptions::skip_leading_junk` is set.
+pub const MAX_LEADING_JUNK: u64 = 0x400;
+
+
 impl<R: Read + Seek> BlorbCursor<R> {
 
     /// Returns a `BlorbCursor` using the given blorb file. The blorb file
-    /// is parsed and validated as part of this call. A `std::io::Error`
-    /// is returned if an error occurs with accessing the file or if the
+    /// is parsed and validated as part of this call. A `BlorbError` is
+    /// returned if an error occurs with accessing the file or if the
     /// file is invalid.
     pub fn from_file(src: R) -> Result<BlorbCursor<R>> {
-        let mut src = src;
+        BlorbCursor::from_file_with_options(src, BlorbOptions::default())
+    }
 
-        // validate the file is a blorb form
-        let form = (&mut src).read_form_data()?;
-        if &form.id != b"IFRS" {
-            return Err(Error::new(ErrorKind::InvalidInput,
-                "file is not blorb"));
+    /// Returns a `BlorbCursor` using the given blorb file, opened using
+    /// the given `BlorbOptions`. Otherwise, this behaves the same as
+    /// `BlorbCursor::from_file`. When leading junk is skipped, offsets
+    /// in the blorb are taken relative to the `FORM` header found.
+    pub fn from_file_with_options(src: R, options: BlorbOptions)
+            -> Result<BlorbCursor<R>> {
+        let mut src = src;
+        let mut start = src.stream_position()?;
+        if options.skip_leading_junk {
+            if let Some(offset) = find_form_header(&mut src)? {
+                start += offset;
+            }
+            src.seek(SeekFrom::Start(start))?;
         }
 
-        // validate the first chunk in the file is the index, and load
-        // the index.
-        if let Chunk::ResourceIndex{index} = src.read_chunk()? {
-            Ok(BlorbCursor{len: form.len, index: index, file: src})
-        } else {
-            Err(Error::new(ErrorKind::InvalidInput,
-                "blorb missing resource index"))
-        }
+        let (len, index) = read_header(&mut src, start, &options)?;
+        Ok(BlorbCursor{len, index, base: start, options, file: src})
+    }
+
+    /// Re-reads the `FORM` header and resource index from the start of
+    /// the blorb, replacing those read when the cursor was created. This
+    /// allows the cursor to follow changes made to the underlying file.
+    /// The header and index are validated using the `BlorbOptions` the
+    /// cursor was opened with. If leading junk was skipped when opening
+    /// the blorb, the header is expected at the same offset. A
+    /// `BlorbError` is returned if an error occurs reading the file or
+    /// if the file is no longer a valid blorb, in which case the cursor
+    /// is left unchanged.
+    pub fn reload(&mut self) -> Result<()> {
+        self.file.seek(SeekFrom::Start(self.base))?;
+        let (len, index) =
+            read_header(&mut self.file, self.base, &self.options)?;
+        self.len = len;
+        self.index = index;
+        Ok(())
     }
 
     /// Using the given index, looks up a blorb resource and load the
     /// resource chunk into memory. This chunk is then returned to the
-    /// caller. An `std::io::Error` is returned if there is an exception
+    /// caller. A `BlorbError` is returned if there is an exception
     /// while loading the resource into memory, if the loaded data is
     /// invalid, or if a resource is requested which is not identified
     /// in the `ResourceIndex`.
     pub fn load_resource(&mut self, usage: Usage, index: u32) -> Result<Chunk> {
-        let start = match usage {
-            Usage::Pict => {
-                match self.index.pictures.get(&(index as usize)) {
-                    Some(entry) => entry.start,
-                    None => return Err(Error::new(ErrorKind::NotFound,
-                        "no entry associated with the given index")),
-                }
-            },
-            Usage::Snd => {
-                match self.index.sounds.get(&(index as usize)) {
-                    Some(entry) => entry.start,
-                    None => return Err(Error::new(ErrorKind::NotFound,
-                        "no entry associated with the given index")),
-                }
-            },
-            Usage::Data => {
-                match self.index.data.get(&(index as usize)) {
-                    Some(entry) => entry.start,
-                    None => return Err(Error::new(ErrorKind::NotFound,
-                        "no entry associated with the given index")),
-                }
+        let start = self.entry_start(usage, index)?;
+        self.file.seek(SeekFrom::Start(self.base + start as u64))?;
+        (&mut self.file).read_chunk()
+    }
+
+    /// Loads the resource chunk identified by the given `IndexEntry`
+    /// into memory, as returned by `BlorbCursor::entries`. The entry is
+    /// not looked up in the `ResourceIndex`; the chunk at its starting
+    /// address is read directly. A `BlorbError` is returned if there is
+    /// an exception while loading the chunk, or if the loaded data is
+    /// invalid.
+    pub fn load_entry(&mut self, entry: &IndexEntry) -> Result<Chunk> {
+        self.file.seek(SeekFrom::Start(self.base + entry.start as u64))?;
+        self.file.read_chunk()
+    }
+
+    /// Using the given index, looks up a blorb resource and loads its
+    /// chunk metadata and chunk data into memory, without interpreting
+    /// the data as a `Chunk`. The pad byte following odd length chunks
+    /// is not included, and for `FORM` chunks, the data begins with the
+    /// 4 byte form id. A `BlorbError` is returned if there is an
+    /// exception loading the resource, or if a resource is requested
+    /// which is not identified in the `ResourceIndex`.
+    pub fn load_resource_raw(&mut self, usage: Usage, index: u32)
+            -> Result<(ChunkData, Vec<u8>)> {
+        let meta = self.resource_info(usage, index)?;
+        let data = self.file.read_exact_vec(meta.len)?;
+        Ok((meta, data))
+    }
+
+    /// Using the given index, looks up a blorb resource and reads only
+    /// the chunk metadata of the resource, without loading the chunk
+    /// data into memory. The `len` of the returned `ChunkData` is the
+    /// length declared in the chunk header, excluding the pad byte of
+    /// odd length chunks, so it can be used to re-emit the chunk
+    /// exactly. After this call, the underlying file is left
+    /// positioned at the start of the resource's chunk data. An
+    /// `BlorbError` is returned if there is an exception reading
+    /// the chunk metadata, or if a resource is requested which is not
+    /// identified in the `ResourceIndex`.
+    pub fn resource_info(&mut self, usage: Usage, index: u32)
+            -> Result<ChunkData> {
+        let start = self.entry_start(usage, index)?;
+        self.file.seek(SeekFrom::Start(self.base + start as u64))?;
+        self.file.read_chunk_data()
+    }
+
+    /// Using the given index, looks up a blorb resource and returns the
+    /// byte range of its chunk data in the file, as the absolute offset
+    /// of the chunk data and its declared length. Only the chunk header
+    /// is read. The range excludes the chunk header and the pad byte of
+    /// odd length chunks. A `BlorbError` is returned if there is an
+    /// exception reading the chunk header, or if a resource is requested
+    /// which is not identified in the `ResourceIndex`.
+    pub fn resource_range(&mut self, usage: Usage, index: u32)
+            -> Result<(u64, u32)> {
+        let start = self.entry_start(usage, index)?;
+        let meta = self.resource_info(usage, index)?;
+        Ok((self.base + start as u64 + 0x8, meta.len))
+    }
+
+    /// Using the given index, looks up a blorb resource and returns a
+    /// `ResourceReader` which streams the resource's chunk data from
+    /// the file, rather than loading it into memory. An
+    /// `BlorbError` is returned if there is an exception reading
+    /// the chunk metadata, or if a resource is requested which is not
+    /// identified in the `ResourceIndex`.
+    pub fn open_resource<'a>(&'a mut self, usage: Usage, index: u32)
+            -> Result<ResourceReader<'a, R>> {
+        let meta = self.resource_info(usage, index)?;
+        let len = meta.len as u64;
+        Ok(ResourceReader{meta, inner: (&mut self.file).take(len)})
+    }
+
+    /// Skips over the data of the chunk described by the given
+    /// `ChunkData`, including the pad byte of odd length chunks, by
+    /// seeking rather than reading. The file is expected to be
+    /// positioned at the start of the chunk's data, as left by
+    /// `BlorbCursor::resource_info`. Returns the new position in the
+    /// file, or a `BlorbError` if there is an exception seeking.
+    pub fn skip_chunk(&mut self, meta: &ChunkData) -> Result<u64> {
+        let len = meta.len as i64;
+        Ok(self.file.seek(SeekFrom::Current(len + (len & 1)))?)
+    }
+
+    /// Writes every resource in the blorb's resource index to its own
+    /// file in the given directory, which is created if needed. Files
+    /// are named by usage and resource number, with an extension
+    /// inferred from the chunk type, such as `pict-1.png`, `snd-3.ogg`,
+    /// or `exec.ulx`. Each file holds the resource's chunk data, except
+    /// for `FORM` chunks such as AIFF sounds, which are written with
+    /// their full `FORM` envelope. Resource data is streamed rather
+    /// than loaded into memory. Returns the paths of the written files,
+    /// or a `BlorbError` if there is an exception reading a resource or
+    /// writing a file.
+    pub fn extract_all<P: AsRef<Path>>(&mut self, dir: P)
+            -> Result<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        let entries: Vec<_> = self.entries()
+            .map(|entry| (entry.usage, entry.num))
+            .collect();
+        let mut paths = Vec::with_capacity(entries.len());
+        for (usage, num) in entries {
+            let mut reader = self.open_resource(usage, num)?;
+            let meta = reader.chunk_data().clone();
+
+            let mut header = Vec::new();
+            let extension = if &meta.id == b"FORM" {
+                let mut id = [0x0; 0x4];
+                reader.read_exact(&mut id)?;
+                header.extend_from_slice(b"FORM\0\0\0\0");
+                BigEndian::write_u32(&mut header[0x4..0x8], meta.len);
+                header.extend_from_slice(&id);
+                id_file_extension(&id)
+            } else {
+                id_file_extension(&meta.id)
+            };
+            let extension = extension.unwrap_or("bin");
+
+            let name = match usage {
+                Usage::Pict => format!("pict-{}.{}", num, extension),
+                Usage::Snd => format!("snd-{}.{}", num, extension),
+                Usage::Data => format!("data-{}.{}", num, extension),
+                Usage::Exec => format!("exec.{}", extension),
+            };
+            let path = dir.join(name);
+            let mut file = File::create(&path)?;
+            file.write_all(&header)?;
+            io::copy(&mut reader, &mut file)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Returns whether the resource with the given usage and index is
+    /// identified in the blorb's resource index, and so can be passed
+    /// to `BlorbCursor::load_resource`. No reads are made from the
+    /// file. As a blorb holds at most one executable, the index is not
+    /// considered for `Usage::Exec`.
+    pub fn contains(&self, usage: Usage, index: u32) -> bool {
+        self.entry_start(usage, index).is_ok()
+    }
+
+    /// Returns the number of resources with the given usage in the
+    /// blorb's resource index.
+    pub fn count(&self, usage: Usage) -> usize {
+        match usage {
+            Usage::Pict => self.index.pictures.len(),
+            Usage::Snd => self.index.sounds.len(),
+            Usage::Data => self.index.data.len(),
+            Usage::Exec => self.index.exec.iter().count(),
+        }
+    }
+
+    /// Returns the total number of resources in the blorb's resource
+    /// index.
+    pub fn total_resources(&self) -> usize {
+        [Usage::Pict, Usage::Snd, Usage::Data, Usage::Exec].iter()
+            .map(|&usage| self.count(usage))
+            .sum()
+    }
+
+    /// Returns the size of the blorb in bytes, as the length declared
+    /// by the `FORM` header plus the 8 byte header itself. This is the
+    /// declared size, which may not match the size of the file if the
+    /// blorb is corrupt; see `BlorbCursor::validate`.
+    pub fn file_size(&self) -> u64 {
+        self.len as u64 + 0x8
+    }
+
+    /// Returns the number of entries declared by the header of the
+    /// blorb's resource index. A header declaring more entries than the
+    /// index chunk holds is rejected with `BlorbError::BadIndexLength`
+    /// when the blorb is opened, so this only differs from
+    /// `total_resources` if the index holds entries sharing the same
+    /// usage and resource number, and is likely corrupt.
+    pub fn declared_count(&self) -> u32 {
+        self.index.declared_count
+    }
+
+    /// Returns a `ChunkIter` over the top level chunks of the blorb, in
+    /// file order, starting with the resource index. Each chunk's
+    /// metadata is yielded without loading its data, which can then be
+    /// loaded using `ChunkIter::load`.
+    pub fn chunks<'a>(&'a mut self) -> ChunkIter<'a, R> {
+        let pos = self.base + 0xC;
+        ChunkIter{cursor: self, pos, current: None, done: false}
+    }
+
+    /// Returns an iterator over every entry in the blorb's resource
+    /// index. See `ResourceIndex::entries` for the iteration order.
+    pub fn entries(&self) -> ::std::vec::IntoIter<&IndexEntry> {
+        self.index.entries()
+    }
+
+    /// Returns an iterator over the entries of the given usage in the
+    /// blorb's resource index, ordered by resource number.
+    pub fn entries_of(&self, usage: Usage)
+            -> impl Iterator<Item = &IndexEntry> {
+        self.index.entries().filter(move |entry| entry.usage == usage)
+    }
+
+    /// Using only the resource number, looks up a blorb resource under
+    /// any usage and loads the resource chunk into memory. Since the
+    /// same number may be used by resources of different usages, the
+    /// usages are searched in the order `Exec`, `Pict`, `Snd`, then
+    /// `Data`, and the first match is returned. A `BlorbError` is
+    /// returned if no resource has the given number, or if there is an
+    /// exception while loading the resource into memory.
+    pub fn find_resource(&mut self, number: u32) -> Result<Chunk> {
+        let key = number as usize;
+        let exec = match self.index.exec {
+            Some(ref entry) if entry.num == number => Some(entry.start),
+            _ => None,
+        };
+        let start = exec
+            .or_else(|| self.index.pictures.get(&key).map(|entry| entry.start))
+            .or_else(|| self.index.sounds.get(&key).map(|entry| entry.start))
+            .or_else(|| self.index.data.get(&key).map(|entry| entry.start));
+
+        match start {
+            Some(start) => {
+                self.file.seek(SeekFrom::Start(self.base + start as u64))?;
+                self.file.read_chunk()
             },
-            Usage::Exec => {
-                match self.index.exec {
-                    Some(ref entry) => entry.start,
-                    None => return Err(Error::new(ErrorKind::NotFound,
-                        "no entry associated with the given index")),
-                }
+            None => Err(BlorbError::NotFound),
+        }
+    }
+
+    /// Scans the top level chunks of the blorb for the first chunk with
+    /// the given identifier, and loads it into memory. This is used to
+    /// access chunks which are not resources, such as the `AUTH` or
+    /// `(c) ` chunks. `None` is returned if the blorb has no such
+    /// chunk. A `BlorbError` is returned if there is an exception while
+    /// loading the chunk into memory, or if the loaded data is invalid.
+    pub fn load_chunk(&mut self, id: &[u8; 0x4]) -> Result<Option<Chunk>> {
+        match self.find_chunk(id)? {
+            Some(meta) => self.file.read_from_chunk_data(meta).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Looks up the release number of the blorb. The top level chunks
+    /// of the blorb are scanned to find the `RelN` chunk. `None` is
+    /// returned if the blorb has no release number. A `BlorbError` is
+    /// returned if there is an exception reading from the file, or if
+    /// the `RelN` chunk is not valid.
+    pub fn release_number(&mut self) -> Result<Option<u16>> {
+        match self.load_chunk(b"RelN")? {
+            Some(Chunk::ReleaseNumber{number}) => Ok(Some(number)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Looks up the metadata of the blorb. The top level chunks of the
+    /// blorb are scanned to find the `IFmd` chunk, and its contents are
+    /// read as a string, decoded as latin-1 if they are not valid utf-8.
+    /// `None` is returned if the blorb has no metadata. A `BlorbError`
+    /// is returned if there is an exception reading from the file.
+    pub fn metadata(&mut self) -> Result<Option<String>> {
+        match self.load_chunk(b"IFmd")? {
+            Some(Chunk::Metadata{info}) => Ok(Some(info)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Loads the executable resource of the blorb into memory, as with
+    /// `BlorbCursor::load_resource` for the `Exec` entry of the
+    /// `ResourceIndex`. `None` is returned if the blorb has no
+    /// executable resource. A `BlorbError` is returned if there is an
+    /// exception while loading the executable, or if the loaded data is
+    /// invalid.
+    pub fn load_executable(&mut self) -> Result<Option<Chunk>> {
+        if self.index.exec.is_none() {
+            return Ok(None);
+        }
+        self.load_resource(Usage::Exec, 0).map(Some)
+    }
+
+    /// Checks the IFID of the executable resource against the IFIDs in
+    /// the blorb's metadata. The IFID of the executable is derived with
+    /// `Chunk::ifid`, and the metadata is parsed with `IfMetadata::parse`.
+    /// Returns whether the derived IFID matches any IFID listed in the
+    /// metadata, ignoring case. A `BlorbError::IfidUnavailable` is
+    /// returned if the blorb has no executable, no metadata, or no IFID
+    /// in its metadata, or if no IFID can be derived from the
+    /// executable. Another `BlorbError` is returned if there is an
+    /// exception reading from the file, or if the metadata is invalid.
+    pub fn verify_ifid(&mut self) -> Result<bool> {
+        let exec = match self.load_executable()? {
+            Some(exec) => exec,
+            None => return Err(BlorbError::IfidUnavailable(
+                "blorb has no executable".to_string())),
+        };
+        let ifid = match exec.ifid() {
+            Some(ifid) => ifid,
+            None => return Err(BlorbError::IfidUnavailable(format!(
+                "no IFID can be derived from {:?} executable",
+                String::from_utf8_lossy(&exec.id())))),
+        };
+        let metadata = match self.metadata()? {
+            Some(xml) => IfMetadata::parse(&xml)?,
+            None => return Err(BlorbError::IfidUnavailable(
+                "blorb has no metadata".to_string())),
+        };
+        if metadata.ifids.is_empty() {
+            return Err(BlorbError::IfidUnavailable(
+                "metadata has no IFID".to_string()));
+        }
+        Ok(metadata.ifids.iter().any(|id| id.eq_ignore_ascii_case(&ifid)))
+    }
+
+    /// Returns the kind of executable held by the blorb, as identified
+    /// by the chunk identifier of the executable resource. Only the
+    /// chunk header is read; the executable is not loaded into memory.
+    /// `None` is returned if the blorb has no executable resource. A
+    /// `BlorbError` is returned if there is an exception reading the
+    /// chunk header from the file.
+    pub fn executable_kind(&mut self) -> Result<Option<ExecKind>> {
+        if self.index.exec.is_none() {
+            return Ok(None);
+        }
+        let meta = self.resource_info(Usage::Exec, 0)?;
+        Ok(Some(ExecKind::from_id(&meta.id)))
+    }
+
+    /// Looks up the debugging information of the blorb. The top level
+    /// chunks of the blorb are scanned to find the `Dbug` chunk, and its
+    /// data is loaded into memory. `None` is returned if the blorb has
+    /// no debugging information. A `BlorbError` is returned if there is
+    /// an exception reading from the file.
+    pub fn debug_info(&mut self) -> Result<Option<Vec<u8>>> {
+        match self.load_chunk(b"Dbug")? {
+            Some(Chunk::Debug{data}) => Ok(Some(data)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Looks up the frontispiece of the blorb, and loads the picture
+    /// resource it refers to. The top level chunks of the blorb are
+    /// scanned to find the `Fspc` chunk. `None` is returned if the blorb
+    /// has no frontispiece. A `BlorbError` is returned if there is an
+    /// exception reading from the file, or if the frontispiece refers
+    /// to a picture which is not identified in the `ResourceIndex`.
+    pub fn frontispiece(&mut self) -> Result<Option<Chunk>> {
+        if self.find_chunk(b"Fspc")?.is_none() {
+            return Ok(None);
+        }
+        let num = self.file.read_u32::<BigEndian>()?;
+        self.load_resource(Usage::Pict, num).map(Some)
+    }
+
+    /// Loads the cover art of the blorb, as the image data of the
+    /// frontispiece and its `ImageFormat`. The frontispiece is resolved
+    /// as with `BlorbCursor::frontispiece`. `None` is returned if the
+    /// blorb has no frontispiece, or if the frontispiece is not a PNG,
+    /// JPEG, or GIF image, such as a `Chunk::Rectangle` placeholder. A
+    /// `BlorbError` is returned if there is an exception reading from
+    /// the file, or if the frontispiece refers to a picture which is
+    /// not identified in the `ResourceIndex`.
+    pub fn cover_art(&mut self) -> Result<Option<(ImageFormat, Vec<u8>)>> {
+        Ok(match self.frontispiece()? {
+            Some(Chunk::Png{data}) => Some((ImageFormat::Png, data)),
+            Some(Chunk::Jpeg{data}) => Some((ImageFormat::Jpeg, data)),
+            Some(Chunk::Gif{data}) => Some((ImageFormat::Gif, data)),
+            _ => None,
+        })
+    }
+
+    /// Validates the resource index against the contents of the file.
+    /// If the length of the file can be found by seeking to its end,
+    /// the length declared by the `FORM` header is first checked to
+    /// match it, allowing for a trailing pad byte, and a
+    /// `BlorbError::BadFormLength` is returned if it does not. Then the
+    /// top level chunks of the blorb are walked, and each entry in the
+    /// resource index is checked to start at one of these chunks, and
+    /// for that chunk to fit within the blorb's `FORM`. A
+    /// `BlorbError::BadOffset` identifying the first offending entry is
+    /// returned if the check fails. Finally, the `Exec` entry is checked
+    /// to point at a known executable chunk, such as `ZCOD` or `GLUL`,
+    /// and a `BlorbError::NotExecutable` is returned if it does not.
+    /// Another `BlorbError` is returned if there is an exception reading
+    /// from the file.
+    pub fn validate(&mut self) -> Result<()> {
+        let form_end = self.base + self.len as u64 + 0x8;
+
+        // sources which cannot seek to their end skip the length check
+        if let Ok(end) = self.file.seek(SeekFrom::End(0)) {
+            if end != form_end && end != form_end + (self.len as u64 & 1) {
+                return Err(BlorbError::BadFormLength{
+                    declared: self.len as u64 + 0x8,
+                    actual: end.saturating_sub(self.base),
+                });
+            }
+        }
+
+        // collect the start and end addresses, and the identifiers, of
+        // the top level chunks
+        let mut chunks = HashMap::new();
+        let mut pos = self.file.seek(SeekFrom::Start(self.base + 0xC))?;
+        while pos + 0x8 <= form_end {
+            let meta = match self.file.read_chunk_data() {
+                Ok(meta) => meta,
+                Err(BlorbError::Io(ref err))
+                    if err.kind() == ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err),
+            };
+            chunks.insert(pos, (pos + 0x8 + meta.len as u64, meta.id));
+            pos = self.skip_chunk(&meta)?;
+        }
+
+        for entry in self.index.entries() {
+            match chunks.get(&(self.base + entry.start as u64)) {
+                Some(&(end, _)) if end <= form_end => (),
+                _ => return Err(BlorbError::BadOffset{
+                    usage: entry.usage,
+                    num: entry.num,
+                    start: entry.start,
+                }),
+            }
+        }
+
+        if let Some(ref entry) = self.index.exec {
+            let (_, id) = chunks[&(self.base + entry.start as u64)];
+            if let ExecKind::Unknown{id} = ExecKind::from_id(&id) {
+                return Err(BlorbError::NotExecutable{id});
+            }
+        }
+        Ok(())
+    }
+
+    /// Scans the top level chunks of the blorb for the first chunk with
+    /// the given identifier, skipping over the data of other chunks.
+    /// If found, the chunk metadata is returned, and the file is left
+    /// positioned at the start of the chunk's data. A `BlorbError` is
+    /// returned if there is an exception reading from the file.
+    fn find_chunk(&mut self, id: &[u8; 0x4]) -> Result<Option<ChunkData>> {
+        let form_end = self.base + self.len as u64 + 0x8;
+        let mut pos = self.file.seek(SeekFrom::Start(self.base + 0xC))?;
+        while pos + 0x8 <= form_end {
+            let meta = self.file.read_chunk_data()?;
+            if &meta.id == id {
+                return Ok(Some(meta));
+            }
+            pos = self.skip_chunk(&meta)?;
+        }
+        Ok(None)
+    }
+
+    /// Returns the starting address of the resource chunk identified by
+    /// the given usage and index. A `BlorbError` is returned if
+    /// the resource is not identified in the `ResourceIndex`.
+    fn entry_start(&self, usage: Usage, index: u32) -> Result<u32> {
+        match self.index.get(usage, index) {
+            Some(entry) => Ok(entry.start),
+            None => Err(BlorbError::NotFound),
+        }
+    }
+}
+
+
+impl BlorbCursor<File> {
+
+    /// Opens the blorb file at the given path, and returns a
+    /// `BlorbCursor` using the file. See `BlorbCursor::from_file` for
+    /// details on the validation performed. A `BlorbError` is
+    /// returned if the file cannot be opened, or if the file is invalid.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<BlorbCursor<File>> {
+        BlorbCursor::from_file(File::open(path)?)
+    }
+}
+
+
+impl<T: AsRef<[u8]>> BlorbCursor<Cursor<T>> {
+
+    /// Returns a `BlorbCursor` using a blorb held in memory, such as a
+    /// `Vec<u8>` or a `&[u8]`. See `BlorbCursor::from_file` for details
+    /// on the validation performed. A `BlorbError` is returned if
+    /// the blorb is invalid.
+    pub fn from_bytes(bytes: T) -> Result<BlorbCursor<Cursor<T>>> {
+        BlorbCursor::from_file(Cursor::new(bytes))
+    }
+
+    /// Using the given index, looks up a blorb resource and returns its
+    /// chunk metadata, along with its chunk data borrowed from the blorb
+    /// held in memory, rather than copied. The pad byte following odd
+    /// length chunks is not included, and for `FORM` chunks, the data
+    /// begins with the 4 byte form id. A `BlorbError` is returned if
+    /// the chunk extends past the end of the blorb, if the address of
+    /// the chunk overflows, or if a resource is requested which is not
+    /// identified in the `ResourceIndex`.
+    pub fn load_resource_borrowed(&self, usage: Usage, index: u32)
+            -> Result<(ChunkData, &[u8])> {
+        let bytes = self.file.get_ref().as_ref();
+        let entry = self.entry_start(usage, index)?;
+        let eof = || Error::new(ErrorKind::UnexpectedEof,
+            "file ended before chunk fully read");
+
+        // the chunk bounds are checked, as the addition may overflow on
+        // targets with a 32 bit usize.
+        let start = (self.base as usize).checked_add(entry as usize)
+            .ok_or(BlorbError::BadOffset{usage, num: index, start: entry})?;
+        let body = start.checked_add(0x8).ok_or_else(eof)?;
+        let header = bytes.get(start..body).ok_or_else(eof)?;
+        let mut id = [0x0; 0x4];
+        id.copy_from_slice(&header[0x0..0x4]);
+        let len = BigEndian::read_u32(&header[0x4..0x8]);
+
+        let end = body.checked_add(len as usize).ok_or_else(eof)?;
+        let data = bytes.get(body..end).ok_or_else(eof)?;
+        Ok((ChunkData{id, len}, data))
+    }
+}
+
+
+impl Blorb {
+
+    /// Reads a full blorb from the given source into a `Blorb`. The
+    /// source is read in a single pass, and the resources are matched
+    /// to their resource index entries by position. A `BlorbError` is
+    /// returned if an error occurs reading the source, if the blorb is
+    /// invalid, or if a resource index entry does not point at the
+    /// start of a chunk.
+    pub fn load<R: Read>(src: R) -> Result<Blorb> {
+        let mut src = src;
+        let form = src.read_form_data()?;
+        if &form.id != b"IFRS" {
+            return Err(BlorbError::NotBlorb);
+        }
+        let form_end = form.len as u64 + 0x8;
+
+        let meta = src.read_chunk_data()?;
+        let index_len = meta.len as u64;
+        let index = match src.read_from_chunk_data(meta)? {
+            Chunk::ResourceIndex{index} => index,
+            _ => return Err(BlorbError::MissingIndex),
+        };
+
+        // read the remaining chunks, keyed by their starting address
+        let mut chunks = HashMap::new();
+        let mut order = Vec::new();
+        let mut pos = 0xC + 0x8 + index_len + (index_len & 1);
+        while pos + 0x8 <= form_end {
+            let meta = src.read_chunk_data()?;
+            let len = meta.len as u64;
+            chunks.insert(pos, src.read_from_chunk_data(meta)?);
+            order.push(pos);
+            pos += 0x8 + len + (len & 1);
+        }
+
+        // count the entries pointing at each chunk, so a chunk is only
+        // cloned when it is shared by more than one entry
+        let mut refs: HashMap<u64, usize> = HashMap::new();
+        for entry in index.entries() {
+            *refs.entry(entry.start as u64).or_insert(0) += 1;
+        }
+
+        let mut blorb = Blorb{
+            index,
+            pictures: HashMap::new(),
+            sounds: HashMap::new(),
+            data: HashMap::new(),
+            exec: None,
+            metadata: None,
+            frontispiece: None,
+            chunks: Vec::new(),
+        };
+        for entry in blorb.index.entries() {
+            let start = entry.start as u64;
+            let remaining = refs.get_mut(&start).map(|count| {
+                *count -= 1;
+                *count
+            });
+            let chunk = match remaining {
+                Some(0) => chunks.remove(&start),
+                _ => chunks.get(&start).cloned(),
+            };
+            let chunk = match chunk {
+                Some(chunk) => chunk,
+                None => return Err(BlorbError::BadOffset{
+                    usage: entry.usage,
+                    num: entry.num,
+                    start: entry.start,
+                }),
+            };
+            let num = entry.num as usize;
+            match entry.usage {
+                Usage::Pict => {blorb.pictures.insert(num, chunk);},
+                Usage::Snd => {blorb.sounds.insert(num, chunk);},
+                Usage::Data => {blorb.data.insert(num, chunk);},
+                Usage::Exec => blorb.exec = Some(chunk),
+            }
+        }
+
+        // the chunks left were not referenced by the index
+        for start in order {
+            match chunks.remove(&start) {
+                Some(Chunk::Metadata{info}) => blorb.metadata = Some(info),
+                Some(Chunk::Frontispiece{num}) =>
+                    blorb.frontispiece = Some(num),
+                Some(chunk) => blorb.chunks.push(chunk),
+                None => (),
             }
+        }
+        Ok(blorb)
+    }
+}
+
+
+/// Reads the `FORM` header and resource index of a blorb, starting at
+/// the current position of the source, which is at offset `start`.
+/// Returns the length declared by the `FORM` header and the resource
+/// index. A source ending inside the `FORM` header is reported as
+/// `BlorbError::TooShortForBlorb`. Another `BlorbError` is returned if
+/// an error occurs reading the source, or if the blorb is not valid.
+fn read_header<R: Read + Seek + ?Sized>(
+        src: &mut R, start: u64, options: &BlorbOptions)
+        -> Result<(u32, ResourceIndex)> {
+    let form = match src.read_form_data() {
+        Err(BlorbError::Io(ref err))
+                if err.kind() == ErrorKind::UnexpectedEof => {
+            let end = src.seek(SeekFrom::End(0))?;
+            return Err(BlorbError::TooShortForBlorb{
+                available: end.saturating_sub(start),
+            });
+        },
+        form => form?,
+    };
+    if &form.id != b"IFRS" {
+        return Err(BlorbError::NotBlorb);
+    }
+    Ok((form.len, read_index(src, options)?))
+}
+
+
+/// Reads the resource index chunk at the current position of the
+/// source, validating it according to the given `BlorbOptions`. A
+/// `BlorbError::MissingIndex` is returned if the chunk is not a `RIdx`
+/// chunk. Another `BlorbError` is returned if an error occurs reading
+/// the source, or if the index is not valid.
+pub(crate) fn read_index<R: Read + ?Sized>(
+        src: &mut R, options: &BlorbOptions) -> Result<ResourceIndex> {
+    let meta = src.read_chunk_data()?;
+    if &meta.id != b"RIdx" {
+        return Err(BlorbError::MissingIndex);
+    }
+    if !options.reject_duplicate_entries {
+        return match src.read_from_chunk_data(meta)? {
+            Chunk::ResourceIndex{index} => Ok(index),
+            _ => Err(BlorbError::MissingIndex),
         };
+    }
 
-        self.file.seek(SeekFrom::Start(start as u64))?;
-        (&mut self.file).read_chunk()
+    let mut index = ResourceIndex::new();
+    for entry in src.read_index_entries(meta.len)? {
+        if index.get(entry.usage, entry.num).is_some() {
+            return Err(BlorbError::DuplicateEntry{
+                usage: entry.usage,
+                num: entry.num,
+            });
+        }
+        index.insert(entry);
+        index.declared_count += 1;
+    }
+    Ok(index)
+}
+
+
+/// Searches the first `MAX_LEADING_JUNK` bytes of the source, from its
+/// current position, for the header of a `FORM` of type `IFRS`. Returns
+/// the offset of the header from the current position, or `None` if it
+/// is not found. The position of the source is left unspecified.
+pub(crate) fn find_form_header<R: Read>(src: &mut R) -> Result<Option<u64>> {
+    let mut head = Vec::new();
+    src.take(MAX_LEADING_JUNK + 0xC).read_to_end(&mut head)?;
+    let offset = head.windows(0xC)
+        .position(|header| &header[0x0..0x4] == b"FORM"
+            && &header[0x8..0xC] == b"IFRS");
+    Ok(offset.map(|offset| offset as u64))
+}
+
+
+/// Streams the chunk data of a single blorb resource.
+///
+/// A `ResourceReader` is returned by `BlorbCursor::open_resource`, and
+/// implements `std::io::Read`, yielding exactly the `len` bytes of the
+/// resource's chunk data. The chunk header and the pad byte following
+/// odd length chunks are not included. For `FORM` chunks, the data
+/// begins with the 4 byte form id.
+pub struct ResourceReader<'a, R: 'a + Read + Seek + ?Sized> {
+    meta: ChunkData,
+    inner: Take<&'a mut R>,
+}
+
+
+impl<'a, R: Read + Seek + ?Sized> ResourceReader<'a, R> {
+
+    /// Returns the chunk metadata of the resource being read.
+    pub fn chunk_data(&self) -> &ChunkData {
+        &self.meta
+    }
+}
+
+
+impl<'a, R: Read + Seek + ?Sized> Read for ResourceReader<'a, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        if read == 0 && !buf.is_empty() && self.inner.limit() > 0 {
+            return Err(Error::new(ErrorKind::UnexpectedEof,
+                "file ended before chunk fully read"));
+        }
+        Ok(read)
+    }
+}
+
+
+/// Iterates over the top level chunks of a blorb.
+///
+/// A `ChunkIter` is returned by `BlorbCursor::chunks`, and yields the
+/// `ChunkData` of each chunk in the blorb's `FORM`, in file order.
+/// Chunk data is only read when requested with `ChunkIter::load`;
+/// otherwise, the iterator seeks past it to the next chunk. Iteration
+/// ends when the form length is exhausted, or after the first error.
+pub struct ChunkIter<'a, R: 'a + Read + Seek> {
+    cursor: &'a mut BlorbCursor<R>,
+    pos: u64,
+    current: Option<(u64, ChunkData)>,
+    done: bool,
+}
+
+
+impl<'a, R: Read + Seek> ChunkIter<'a, R> {
+
+    /// Loads the data of the chunk most recently yielded by the
+    /// iterator. A `BlorbError::NotFound` is returned if no chunk has
+    /// been yielded yet, or another `BlorbError` if there is an
+    /// exception loading the chunk, or if the loaded data is invalid.
+    pub fn load(&mut self) -> Result<Chunk> {
+        let (start, meta) = match self.current {
+            Some((start, ref meta)) => (start, meta.clone()),
+            None => return Err(BlorbError::NotFound),
+        };
+        self.cursor.file.seek(SeekFrom::Start(start))?;
+        self.cursor.file.read_from_chunk_data(meta)
+    }
+}
+
+
+impl<'a, R: Read + Seek> Iterator for ChunkIter<'a, R> {
+    type Item = Result<ChunkData>;
+
+    fn next(&mut self) -> Option<Result<ChunkData>> {
+        let form_end = self.cursor.base + self.cursor.len as u64 + 0x8;
+        if self.done || self.pos + 0x8 > form_end {
+            self.done = true;
+            return None;
+        }
+
+        let file = &mut self.cursor.file;
+        let meta = match file.seek(SeekFrom::Start(self.pos)) {
+            Ok(_) => file.read_chunk_data(),
+            Err(err) => Err(err.into()),
+        };
+        match meta {
+            Ok(meta) => {
+                let len = meta.len as u64;
+                self.current = Some((self.pos + 0x8, meta.clone()));
+                self.pos += 0x8 + len + (len & 1);
+                Some(Ok(meta))
+            },
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            },
+        }
     }
 }
 
@@ -120,14 +929,14 @@ impl<R: Read + Seek> BlorbCursor<R> {
 ///
 /// **TODO**: Eventually, this will be API -- so any internal methods
 /// which are not offering up blorb structs will need to be moved.
-trait ReadBlorbExt : Read {
+pub(crate) trait ReadBlorbExt : Read {
 
     // Helper Methods
     ////////////////////////////////////////////////////////////////////
     // XXX: Find a better location for these methods
 
     /// Reads a 4 byte ASCII string into a `[u8; 0x4]`. Returns a
-    /// `std::io::Error` if a problem arises reading the ascii bytes
+    /// `BlorbError` if a problem arises reading the ascii bytes
     /// from the blorb.
     fn read_id(&mut self) -> Result<[u8; 0x4]> {
         let mut id = [0x0;0x4];
@@ -138,31 +947,49 @@ trait ReadBlorbExt : Read {
     /// Light wrapper around the `std::io::Read::read_to_end` method
     /// which will return a `Vec` with `len` bytes from the file. If
     /// the number of bytes read does not match the expected length, or
-    /// if other issues occur reading from the blorb, a `std::io::Error`
+    /// if other issues occur reading from the blorb, a `BlorbError`
     /// is returned.
     fn read_exact_vec(&mut self, len: u32) -> Result<Vec<u8>> {
         let mut data = Vec::with_capacity(len as usize);
         if len as usize != self.take(len as u64).read_to_end(&mut data)? {
             return Err(Error::new(
                 ErrorKind::UnexpectedEof,
-                "file ended before chunk fully read"));
+                "file ended before chunk fully read").into());
         }
         Ok(data)
     }
 
-    /// Light wrapper around the `std::io::Read::read_to_string` method
-    /// which will return a `String` with `len` bytes from the file. If
-    /// the number of bytes read does not match the expected length, or
-    /// if other issues occur reading from the blorb, a `std::io::Error`
-    /// is returned.
-    fn read_exact_string(&mut self, len: u32) -> Result<String> {
-        let mut data = String::with_capacity(len as usize);
-        if len as usize != self.take(len as u64).read_to_string(&mut data)? {
-            return Err(Error::new(
-                ErrorKind::UnexpectedEof,
-                "file ended before chunk fully read"));
+    /// Consumes the pad byte which follows a chunk of odd length `len`.
+    /// The final chunk of a file may omit its pad byte, so reaching the
+    /// end of the file here is not an error. Returns a `BlorbError` if
+    /// any other issue occurs reading from the blorb.
+    fn read_pad(&mut self, len: u32) -> Result<()> {
+        if len & 1 == 0 {
+            return Ok(());
+        }
+        loop {
+            match self.read(&mut [0x0]) {
+                Ok(_) => return Ok(()),
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => (),
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Reads `len` bytes of text from the file into a `String`. The
+    /// text is decoded as utf-8 if it is valid utf-8, and otherwise as
+    /// latin-1, so any sequence of bytes can be read. Latin-1 text is
+    /// re-encoded as utf-8 by `BlorbWriter`, so it does not round trip
+    /// byte for byte. If the number of bytes read does not match the
+    /// expected length, or if other issues occur reading from the blorb,
+    /// a `BlorbError` is returned.
+    fn read_exact_text(&mut self, len: u32) -> Result<String> {
+        match String::from_utf8(self.read_exact_vec(len)?) {
+            Ok(text) => Ok(text),
+            Err(err) => Ok(err.into_bytes().iter()
+                .map(|&b| b as char)
+                .collect()),
         }
-        Ok(data)
     }
 
     // Blorb metadata methods
@@ -183,7 +1010,7 @@ trait ReadBlorbExt : Read {
     fn read_form_data(&mut self) -> Result<FormData> {
         let meta = self.read_chunk_data()?;
         if &meta.id != b"FORM" {
-            return Err(Error::new(ErrorKind::InvalidInput, "not FORM chunk"));
+            return Err(BlorbError::NotBlorb);
         }
         Ok(FormData{len: meta.len, id: self.read_id()?})
     }
@@ -193,22 +1020,27 @@ trait ReadBlorbExt : Read {
 
     /// Reads a `ChunkData` from the blorb. Then, uses that metadata to
     /// read the chunk data into a `Chunk`. Returns the chunk or the
-    /// `std::io::Error` which occured when reading the chunk.`
+    /// `BlorbError` which occured when reading the chunk.`
     fn read_chunk(&mut self) -> Result<Chunk> {
         let meta = self.read_chunk_data()?;
         self.read_from_chunk_data(meta)
     }
 
     /// Takes a `ChunkData` and returns a `Chunk` based on the the
-    /// metadata. Returns a `io::std::Error` if an issue occurs reading
+    /// metadata. Returns a `BlorbError` if an issue occurs reading
     /// the data from the blorb.
     fn read_from_chunk_data(&mut self, meta: ChunkData) -> Result<Chunk> {
         match &meta.id {
+            b"(c) " => self.read_copyright(meta.len),
             b"ADRI" => self.read_adrift(meta.len),
             b"ADVS" => self.read_adv_sys(meta.len),
             b"AGT " => self.read_agt(meta.len),
             b"ALAN" => self.read_alan(meta.len),
+            b"ANNO" => self.read_annotation(meta.len),
+            b"APal" => self.read_adaptive_palette(meta.len),
+            b"AUTH" => self.read_author(meta.len),
             b"BINA" => self.read_binary(meta.len),
+            b"Dbug" => self.read_debug(meta.len),
             b"EXEC" => self.read_exec(meta.len),
             b"FORM" => self.read_form(meta.len),
             b"Fspc" => self.read_frontispiece(),
@@ -224,8 +1056,12 @@ trait ReadBlorbExt : Read {
             b"MP3 " => self.read_mp3(meta.len),
             b"OGGV" => self.read_ogg(meta.len),
             b"PNG " => self.read_png(meta.len),
+            b"Plte" => self.read_palette(meta.len),
             b"RIdx" => self.read_resource_index(meta.len),
             b"Rect" => self.read_rectangle(),
+            b"RelN" => self.read_release_number(meta.len),
+            b"Reso" => self.read_resolution(meta.len),
+            b"SNam" => self.read_short_name(meta.len),
             b"SONG" => self.read_song(meta.len),
             b"TAD2" => self.read_tads2(meta.len),
             b"TAD3" => self.read_tads3(meta.len),
@@ -250,15 +1086,12 @@ trait ReadBlorbExt : Read {
     // this trait becomes public
 
     /// Read an index entry of a `ResourceIndex` from the blorb. return
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_index_entry(&mut self) -> Result<IndexEntry> {
-        let usage = match &self.read_id()? {
-            b"Pict" => Usage::Pict,
-            b"Snd " => Usage::Snd,
-            b"Data" => Usage::Data,
-            b"Exec" => Usage::Exec,
-            _ => return Err(Error::new(ErrorKind::InvalidInput,
-                "could not identify index entry usage")),
+        let id = self.read_id()?;
+        let usage = match Usage::from_bytes(&id) {
+            Some(usage) => usage,
+            None => return Err(BlorbError::UnknownUsage{id}),
         };
         let num = self.read_u32::<BigEndian>()?;
         let start = self.read_u32::<BigEndian>()?;
@@ -267,174 +1100,274 @@ trait ReadBlorbExt : Read {
     }
 
     /// Read a `Chunk::ResourceIndex` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_resource_index(&mut self, len: u32) -> Result<Chunk> {
-        let num = self.read_u32::<BigEndian>()?;
+        let entries = self.read_index_entries(len)?;
 
-        // validate resource index length
-        if len != num*12 + 4 {
-            return Err(Error::new(ErrorKind::InvalidInput,
-                "length of resource index does not match item length"));
+        // retrieve entries and store in hashmap based on index
+        let mut index = ResourceIndex::new();
+        index.declared_count = entries.len() as u32;
+        for entry in entries {
+            index.insert(entry);
         }
 
-        // retrieve entries and store in hashmap based on index
-        let mut pictures = HashMap::new();
-        let mut sounds = HashMap::new();
-        let mut data = HashMap::new();
-        let mut exec = None;
-        for _ in 0..num {
-            let entry = self.read_index_entry()?;
-            match entry.usage {
-                Usage::Pict => pictures.insert(entry.num as usize, entry),
-                Usage::Snd => sounds.insert(entry.num as usize, entry),
-                Usage::Data => data.insert(entry.num as usize, entry),
-                Usage::Exec => {
-                    exec = Some(entry);
-                    None
-                },
-            };
+        Ok(Chunk::ResourceIndex{index})
+    }
+
+    /// Read the entries of a `Chunk::ResourceIndex` from the blorb
+    /// file, in the order they are stored. Returns a `BlorbError` if
+    /// the blorb data is not valid.
+    fn read_index_entries(&mut self, len: u32) -> Result<Vec<IndexEntry>> {
+        let num = self.read_u32::<BigEndian>()?;
+
+        // validate resource index length. The expected length is found
+        // as a u64, as a large num would overflow a u32.
+        let expected = num as u64 * 12 + 4;
+        if len as u64 != expected {
+            return Err(BlorbError::BadIndexLength{
+                num,
+                expected,
+                found: len,
+            });
         }
-        let pictures = pictures;
-        let sounds = sounds;
-        let data = data;
-        let exec = exec;
 
-        Ok(Chunk::ResourceIndex{index: ResourceIndex{
-            pictures: pictures,
-            sounds: sounds,
-            data: data,
-            exec: exec,
-        }})
+        (0..num).map(|_| self.read_index_entry()).collect()
     }
 
     /// Read a `Chunk::ZCode` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_zcode(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::ZCode{code: code})
     }
 
     /// Read a `Chunk::Glulx` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_glulx(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Glulx{code: code})
     }
 
     /// Read a `Chunk::Tads2` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_tads2(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Tads2{code: code})
     }
 
     /// Read a `Chunk::Tads3` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_tads3(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Tads3{code: code})
     }
 
     /// Read a `Chunk::Hugo` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_hugo(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Hugo{code: code})
     }
 
     /// Read a `Chunk::Alan` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_alan(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Alan{code: code})
     }
 
     /// Read a `Chunk::Adrift` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_adrift(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Adrift{code: code})
     }
 
     /// Read a `Chunk::Level9` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_level9(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Level9{code: code})
     }
 
     /// Read a `Chunk::Agt` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_agt(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Agt{code: code})
     }
 
     /// Read a `Chunk::MagneticScrolls` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_magnetic_scrolls(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::MagneticScrolls{code: code})
     }
 
     /// Read a `Chunk::AdvSys` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_adv_sys(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::AdvSys{code: code})
     }
 
     /// Read a `Chunk::Exec` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_exec(&mut self, len: u32) -> Result<Chunk> {
         let code = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Exec{code: code})
     }
 
     /// Read a `Chunk::Frontispiece` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_frontispiece(&mut self) -> Result<Chunk> {
         Ok(Chunk::Frontispiece{num: self.read_u32::<BigEndian>()?})
     }
 
     /// Read a `Chunk::Metadata` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_metadata(&mut self, len: u32) -> Result<Chunk> {
-        let info = self.read_exact_string(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        let info = self.read_exact_text(len)?;
+        self.read_pad(len)?;
         Ok(Chunk::Metadata{info: info})
     }
 
+    /// Read a `Chunk::Author` data from the blorb file. Returns
+    /// a `BlorbError` if the blorb data is not valid.
+    fn read_author(&mut self, len: u32) -> Result<Chunk> {
+        let text = self.read_exact_text(len)?;
+        self.read_pad(len)?;
+        Ok(Chunk::Author{text})
+    }
+
+    /// Read a `Chunk::Copyright` data from the blorb file. Returns
+    /// a `BlorbError` if the blorb data is not valid.
+    fn read_copyright(&mut self, len: u32) -> Result<Chunk> {
+        let text = self.read_exact_text(len)?;
+        self.read_pad(len)?;
+        Ok(Chunk::Copyright{text})
+    }
+
+    /// Read a `Chunk::Annotation` data from the blorb file. Returns
+    /// a `BlorbError` if the blorb data is not valid.
+    fn read_annotation(&mut self, len: u32) -> Result<Chunk> {
+        let text = self.read_exact_text(len)?;
+        self.read_pad(len)?;
+        Ok(Chunk::Annotation{text})
+    }
+
+    /// Read a `Chunk::ReleaseNumber` data from the blorb file. Returns
+    /// a `BlorbError` if the blorb data is not valid.
+    fn read_release_number(&mut self, len: u32) -> Result<Chunk> {
+        if len != 2 {
+            return Err(BlorbError::BadLength{expected: 2, found: len});
+        }
+        Ok(Chunk::ReleaseNumber{number: self.read_u16::<BigEndian>()?})
+    }
+
+    /// Read a `Chunk::AdaptivePalette` data from the blorb file. Returns
+    /// a `BlorbError` if the blorb data is not valid.
+    fn read_adaptive_palette(&mut self, len: u32) -> Result<Chunk> {
+        // validate the length holds a whole number of resource numbers
+        if len % 4 != 0 {
+            return Err(BlorbError::BadLength{
+                expected: len - len % 4,
+                found: len,
+            });
+        }
+
+        let mut entries = Vec::with_capacity(len as usize / 4);
+        for _ in 0..len / 4 {
+            entries.push(self.read_u32::<BigEndian>()?);
+        }
+        Ok(Chunk::AdaptivePalette{entries})
+    }
+
+    /// Read a `Chunk::Palette` or `Chunk::DirectPalette` data from the
+    /// blorb file. A single byte body holds a direct color depth, which
+    /// must be 16 or 32. Returns a `BlorbError` if the blorb data is not
+    /// valid.
+    fn read_palette(&mut self, len: u32) -> Result<Chunk> {
+        if len == 1 {
+            let depth = self.read_u8()?;
+            self.read_pad(len)?;
+            return match depth {
+                16 | 32 => Ok(Chunk::DirectPalette{depth}),
+                _ => Err(BlorbError::BadPaletteDepth{depth}),
+            };
+        }
+
+        // validate the length holds a positive whole number of rgb colors
+        if len == 0 || len % 3 != 0 {
+            return Err(BlorbError::BadLength{
+                expected: (len - len % 3).max(3),
+                found: len,
+            });
+        }
+
+        let data = self.read_exact_vec(len)?;
+        self.read_pad(len)?;
+        let colors = data.chunks(3)
+            .map(|rgb| (rgb[0], rgb[1], rgb[2]))
+            .collect();
+        Ok(Chunk::Palette{colors})
+    }
+
+    /// Read a `Chunk::Debug` data from the blorb file. Returns
+    /// a `BlorbError` if the blorb data is not valid.
+    fn read_debug(&mut self, len: u32) -> Result<Chunk> {
+        let data = self.read_exact_vec(len)?;
+        self.read_pad(len)?;
+        Ok(Chunk::Debug{data})
+    }
+
+    /// Read a `Chunk::ShortName` data from the blorb file. The name is
+    /// decoded as UTF-16 with big endian code units. Invalid code
+    /// units, and a trailing byte left over from an odd length, are
+    /// replaced by `U+FFFD` rather than rejected. Returns a
+    /// `BlorbError` if the blorb data can not be read.
+    fn read_short_name(&mut self, len: u32) -> Result<Chunk> {
+        let data = self.read_exact_vec(len)?;
+        self.read_pad(len)?;
+        let units: Vec<u16> = data.chunks(0x2)
+            .map(|unit| match unit.len() {
+                0x2 => BigEndian::read_u16(unit),
+                _ => 0xFFFD,
+            })
+            .collect();
+        Ok(Chunk::ShortName{name: String::from_utf16_lossy(&units)})
+    }
+
     /// Read a `Chunk::Png` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_png(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Png{data: data})
     }
 
     /// Read a `Chunk::Jpeg` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_jpeg(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Jpeg{data: data})
     }
 
     /// Read a `Chunk::Rectangle` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_rectangle(&mut self) -> Result<Chunk> {
         Ok(Chunk::Rectangle{
             width: self.read_u32::<BigEndian>()?,
@@ -442,110 +1375,1489 @@ trait ReadBlorbExt : Read {
         })
     }
 
-    // XXX: This is done really inefficiently.
+    /// Read a `Chunk::Resolution` data from the blorb file. Returns
+    /// a `BlorbError` if the blorb data is not valid.
+    fn read_resolution(&mut self, len: u32) -> Result<Chunk> {
+        // validate resolution length, which must hold the window sizes
+        // and a whole number of 28 byte image entries.
+        if len < 24 || (len - 24) % 28 != 0 {
+            return Err(BlorbError::BadLength{
+                expected: if len < 24 {24} else {len - (len - 24) % 28},
+                found: len,
+            });
+        }
+
+        let px = self.read_u32::<BigEndian>()?;
+        let py = self.read_u32::<BigEndian>()?;
+        let minx = self.read_u32::<BigEndian>()?;
+        let miny = self.read_u32::<BigEndian>()?;
+        let maxx = self.read_u32::<BigEndian>()?;
+        let maxy = self.read_u32::<BigEndian>()?;
+
+        let mut images = HashMap::new();
+        for _ in 0..(len - 24) / 28 {
+            let image = ImageResolution{
+                num: self.read_u32::<BigEndian>()?,
+                ratnum: self.read_u32::<BigEndian>()?,
+                ratden: self.read_u32::<BigEndian>()?,
+                minnum: self.read_u32::<BigEndian>()?,
+                minden: self.read_u32::<BigEndian>()?,
+                maxnum: self.read_u32::<BigEndian>()?,
+                maxden: self.read_u32::<BigEndian>()?,
+            };
+            images.insert(image.num as usize, image);
+        }
+
+        Ok(Chunk::Resolution{
+            px, py,
+            minx, miny,
+            maxx, maxy,
+            images,
+        })
+    }
+
     /// Read a `Chunk::Aiff` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_aiff(&mut self, len: u32) -> Result<Chunk> {
-        let mut data = Vec::<u8>::with_capacity((len + 0x8) as usize);
-        data.extend_from_slice(b"FORM");
-        data.extend_from_slice(&[0x0;0x4]);
+        // the form header has already been read, so it is rebuilt at
+        // the start of the buffer, and the rest of the form is read
+        // into the buffer after it.
+        let mut data = vec![0x0; 0xC + (len as usize).saturating_sub(0x4)];
+        data[0x0..0x4].copy_from_slice(b"FORM");
         BigEndian::write_u32(&mut data[0x4..0x8], len);
-        data.extend_from_slice(b"AIFF");
-        data.append(&mut self.read_exact_vec(len - 0x4)?);
+        data[0x8..0xC].copy_from_slice(b"AIFF");
+        self.read_exact(&mut data[0xC..])?;
         let data = data;
 
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Aiff{data: data})
     }
 
     /// Read a `Chunk::Ogg` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_ogg(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Ogg{data: data})
     }
 
     /// Read a `Chunk::Mod` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_mod(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Mod{data: data})
     }
 
     /// Read a `Chunk::Song` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_song(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Song{data: data})
     }
 
     /// Read a `Chunk::Text` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_text(&mut self, len: u32) -> Result<Chunk> {
-        let text = self.read_exact_string(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        let text = self.read_exact_text(len)?;
+        self.read_pad(len)?;
         Ok(Chunk::Text{text: text})
     }
 
     /// Read a `Chunk::Binary` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_binary(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Binary{data: data})
     }
 
     /// Read a `Chunk::Gif` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_gif(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Gif{data: data})
     }
 
     /// Read a `Chunk::Wav` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_wav(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Wav{data: data})
     }
 
     /// Read a `Chunk::Midi` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_midi(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Midi{data: data})
     }
 
     /// Read a `Chunk::Mp3` data from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_mp3(&mut self, len: u32) -> Result<Chunk> {
         let data = self.read_exact_vec(len)?;
-        if len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(len)?;
         Ok(Chunk::Mp3{data: data})
     }
 
     /// Read a `Chunk::Unknown` from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_unknown(&mut self, meta: ChunkData) -> Result<Chunk> {
         let data = self.read_exact_vec(meta.len)?;
-        if meta.len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(meta.len)?;
         Ok(Chunk::Unknown{meta: meta, data: data})
     }
 
     /// Read a `Chunk::UnknownForm` from the blorb file. Returns
-    /// a `std::io::Error` if the blorb data is not valid.
+    /// a `BlorbError` if the blorb data is not valid.
     fn read_unknown_form(&mut self, meta: FormData) -> Result<Chunk> {
         let data = self.read_exact_vec(meta.len - 0x4)?;
-        if meta.len & 1 == 1 {self.read_exact(&mut [0x0])?};
+        self.read_pad(meta.len)?;
         Ok(Chunk::UnknownForm{meta: meta, data: data})
     }
 }
 
 
 impl<R: Read + ?Sized> ReadBlorbExt for R {}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::error::BlorbError;
+    use crate::testutil::{
+        Corruption,
+        build_blorb,
+        chunk,
+        corrupt_blorb,
+    };
+    use super::*;
+
+    fn open(blorb: Vec<u8>) -> BlorbCursor<Cursor<Vec<u8>>> {
+        match BlorbCursor::from_file(Cursor::new(blorb)) {
+            Ok(cursor) => cursor,
+            Err(err) => panic!("failed to open blorb: {}", err),
+        }
+    }
+
+    #[test]
+    fn from_file_too_short_for_blorb() {
+        match BlorbCursor::from_bytes(b"FOR").err() {
+            Some(BlorbError::TooShortForBlorb{available: 3}) => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn load_resource_each_usage() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 2, b"OGGV", b"ogg"),
+            (b"Data", 3, b"BINA", b"bin"),
+            (b"Exec", 0, b"GLUL", b"glulx"),
+        ], &[]));
+
+        match cursor.load_resource(Usage::Pict, 1).unwrap() {
+            Chunk::Png{data} => assert_eq!(data, b"png"),
+            _ => panic!("picture did not load as png"),
+        }
+        match cursor.load_resource(Usage::Snd, 2).unwrap() {
+            Chunk::Ogg{data} => assert_eq!(data, b"ogg"),
+            _ => panic!("sound did not load as ogg"),
+        }
+        match cursor.load_resource(Usage::Data, 3).unwrap() {
+            Chunk::Binary{data} => assert_eq!(data, b"bin"),
+            _ => panic!("data did not load as binary"),
+        }
+        match cursor.load_resource(Usage::Exec, 0).unwrap() {
+            Chunk::Glulx{code} => assert_eq!(code, b"glulx"),
+            _ => panic!("exec did not load as glulx"),
+        }
+    }
+
+    #[test]
+    fn load_resource_aiff() {
+        let aiff = b"AIFFCOMM\0\0\0\x02\0\x01";
+        let blorb = build_blorb(&[(b"Snd ", 1, b"FORM", aiff)], &[]);
+        let form = blorb[blorb.len() - aiff.len() - 0x8..].to_vec();
+        let mut cursor = open(blorb);
+
+        match cursor.load_resource(Usage::Snd, 1).unwrap() {
+            Chunk::Aiff{data} => assert_eq!(data, form),
+            _ => panic!("sound did not load as aiff"),
+        }
+    }
+
+    #[test]
+    fn load_resource_text() {
+        let mut cursor = open(build_blorb(&[
+            (b"Data", 1, b"TEXT", b"odd text"),
+            (b"Data", 2, b"TEXT", b"odd text."),
+        ], &[]));
+
+        match cursor.load_resource(Usage::Data, 1).unwrap() {
+            Chunk::Text{text} => assert_eq!(text, "odd text"),
+            _ => panic!("data did not load as text"),
+        }
+        match cursor.load_resource(Usage::Data, 2).unwrap() {
+            Chunk::Text{text} => assert_eq!(text, "odd text."),
+            _ => panic!("data did not load as text"),
+        }
+    }
+
+    #[test]
+    fn find_resource_single_usage() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 2, b"OGGV", b"ogg"),
+        ], &[]));
+
+        match cursor.find_resource(2).unwrap() {
+            Chunk::Ogg{data} => assert_eq!(data, b"ogg"),
+            _ => panic!("resource 2 did not load as ogg"),
+        }
+    }
+
+    #[test]
+    fn find_resource_prefers_exec_then_pict() {
+        let mut cursor = open(build_blorb(&[
+            (b"Data", 0, b"BINA", b"bin"),
+            (b"Pict", 0, b"PNG ", b"png"),
+            (b"Exec", 0, b"GLUL", b"glulx"),
+            (b"Snd ", 1, b"OGGV", b"ogg"),
+            (b"Pict", 1, b"JPEG", b"jpeg"),
+        ], &[]));
+
+        match cursor.find_resource(0).unwrap() {
+            Chunk::Glulx{..} => (),
+            _ => panic!("resource 0 did not prefer exec"),
+        }
+        match cursor.find_resource(1).unwrap() {
+            Chunk::Jpeg{..} => (),
+            _ => panic!("resource 1 did not prefer pict"),
+        }
+    }
+
+    #[test]
+    fn find_resource_absent() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[]));
+
+        match cursor.find_resource(7) {
+            Err(BlorbError::NotFound) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resource_info_png() {
+        let png = [0x89; 0x401];
+        let blorb = build_blorb(&[(b"Pict", 1, b"PNG ", &png)], &[]);
+        let body = (blorb.len() - png.len() - 0x1) as u64;
+        let mut cursor = open(blorb);
+
+        let info = cursor.resource_info(Usage::Pict, 1).unwrap();
+        assert_eq!(&info.id, b"PNG ");
+        assert_eq!(info.len, 0x401);
+        assert_eq!(cursor.file.stream_position().unwrap(), body);
+    }
+
+    #[test]
+    fn entries_mixed() {
+        let cursor = open(build_blorb(&[
+            (b"Exec", 0, b"ZCOD", b"zcode"),
+            (b"Snd ", 4, b"OGGV", b"ogg"),
+            (b"Pict", 3, b"PNG ", b"png"),
+            (b"Data", 5, b"TEXT", b"text"),
+            (b"Pict", 1, b"JPEG", b"jpeg"),
+        ], &[]));
+
+        let entries: Vec<_> = cursor.entries()
+            .map(|entry| (entry.usage, entry.num))
+            .collect();
+        assert_eq!(entries, vec![
+            (Usage::Pict, 1), (Usage::Pict, 3), (Usage::Snd, 4),
+            (Usage::Data, 5), (Usage::Exec, 0),
+        ]);
+    }
+
+    #[test]
+    fn open_resource_streams_chunk_data() {
+        let mut cursor = open(build_blorb(&[
+            (b"Snd ", 1, b"OGGV", b"streamed ogg"),
+            (b"Snd ", 2, b"MOD ", b"mod"),
+        ], &[]));
+
+        let mut reader = cursor.open_resource(Usage::Snd, 2).unwrap();
+        assert_eq!(&reader.chunk_data().id, b"MOD ");
+
+        let mut data = Vec::new();
+        let mut buf = [0x0; 0x2];
+        loop {
+            match reader.read(&mut buf).unwrap() {
+                0 => break,
+                read => data.extend_from_slice(&buf[..read]),
+            }
+        }
+        assert_eq!(data, b"mod");
+        assert_eq!(reader.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn open_resource_truncated() {
+        let mut blorb = build_blorb(&[(b"Snd ", 1, b"OGGV", b"ogg data")], &[]);
+        let len = blorb.len();
+        blorb.truncate(len - 0x4);
+        let mut cursor = open(blorb);
+
+        let mut data = Vec::new();
+        let err = cursor.open_resource(Usage::Snd, 1).unwrap()
+            .read_to_end(&mut data).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+        assert_eq!(data, b"ogg ");
+    }
+
+    #[test]
+    fn from_path_temp_file() {
+        let path = ::std::env::temp_dir().join(
+            format!("blorb-from-path-{}.blorb", ::std::process::id()));
+        ::std::fs::write(&path,
+            build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[])).unwrap();
+
+        let loaded = BlorbCursor::from_path(&path)
+            .and_then(|mut cursor| cursor.load_resource(Usage::Pict, 1));
+        ::std::fs::remove_file(&path).unwrap();
+
+        match loaded.unwrap() {
+            Chunk::Png{data} => assert_eq!(data, b"png"),
+            _ => panic!("picture did not load as png"),
+        }
+    }
+
+    #[test]
+    fn from_bytes_owned_and_borrowed() {
+        let blorb = build_blorb(&[(b"Data", 1, b"BINA", b"bin")], &[]);
+
+        let mut cursor = BlorbCursor::from_bytes(&blorb[..]).unwrap();
+        match cursor.load_resource(Usage::Data, 1).unwrap() {
+            Chunk::Binary{data} => assert_eq!(data, b"bin"),
+            _ => panic!("data did not load as binary"),
+        }
+
+        let mut cursor = BlorbCursor::from_bytes(blorb).unwrap();
+        match cursor.load_resource(Usage::Data, 1).unwrap() {
+            Chunk::Binary{data} => assert_eq!(data, b"bin"),
+            _ => panic!("data did not load as binary"),
+        }
+    }
+
+    #[test]
+    fn from_file_not_blorb() {
+        let mut blorb = build_blorb(&[], &[]);
+        blorb[0x8..0xC].copy_from_slice(b"AIFF");
+        match BlorbCursor::from_bytes(blorb).err() {
+            Some(BlorbError::NotBlorb) => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        match BlorbCursor::from_bytes(b"LIST\0\0\0\x04IFRS").err() {
+            Some(BlorbError::NotBlorb) => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_file_missing_index() {
+        let mut blorb = build_blorb(&[], &[]);
+        blorb[0xC..0x10].copy_from_slice(b"RIDX");
+        match BlorbCursor::from_bytes(blorb).err() {
+            Some(BlorbError::MissingIndex) => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_file_bad_index_length() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        blorb[0x13] += 0x2;
+        match BlorbCursor::from_bytes(blorb).err() {
+            Some(BlorbError::BadIndexLength{num: 1, expected: 16, found: 18})
+                => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_file_unknown_usage() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        blorb[0x18..0x1C].copy_from_slice(b"Pics");
+        match BlorbCursor::from_bytes(blorb).err() {
+            Some(BlorbError::UnknownUsage{id}) => assert_eq!(&id, b"Pics"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_valid() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Snd ", 2, b"OGGV", b"ogg"),
+            (b"Exec", 0, b"GLUL", b"glulx"),
+        ], &[(b"Fspc", b"\0\0\0\x01")]));
+        cursor.validate().unwrap();
+    }
+
+    #[test]
+    fn validate_offset_mid_chunk() {
+        let mut blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 2, b"OGGV", b"ogg"),
+        ], &[]);
+        blorb[0x2F] += 0x2;
+        let mut cursor = open(blorb);
+
+        match cursor.validate() {
+            Err(BlorbError::BadOffset{usage: Usage::Snd, num: 2, ..}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_offset_past_end() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        let len = blorb.len() as u8;
+        blorb[0x23] = len + 0x10;
+        let mut cursor = open(blorb);
+
+        match cursor.validate() {
+            Err(BlorbError::BadOffset{usage: Usage::Pict, num: 1, ..}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_chunk_past_form() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        let len = blorb.len();
+        blorb[len - 0x6] = 0x10;
+        let mut cursor = open(blorb);
+
+        match cursor.validate() {
+            Err(BlorbError::BadOffset{usage: Usage::Pict, num: 1, ..}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_resolution_two_images() {
+        let mut reso = Vec::new();
+        for value in &[600, 400, 0, 0, 1200, 800,
+                1, 1, 1, 0, 0, 0, 0,
+                4, 1, 2, 0, 0, 1, 1] {
+            let mut bytes = [0x0; 0x4];
+            BigEndian::write_u32(&mut bytes, *value);
+            reso.extend_from_slice(&bytes);
+        }
+
+        match Cursor::new(chunk(b"Reso", &reso)).read_chunk().unwrap() {
+            Chunk::Resolution{px, py, minx, miny, maxx, maxy, images} => {
+                assert_eq!((px, py), (600, 400));
+                assert_eq!((minx, miny, maxx, maxy), (0, 0, 1200, 800));
+                assert_eq!(images.len(), 2);
+
+                let image = &images[&1];
+                assert_eq!((image.ratnum, image.ratden), (1, 1));
+                assert_eq!((image.minnum, image.minden), (0, 0));
+                assert_eq!((image.maxnum, image.maxden), (0, 0));
+
+                let image = &images[&4];
+                assert_eq!((image.ratnum, image.ratden), (1, 2));
+                assert_eq!((image.minnum, image.minden), (0, 0));
+                assert_eq!((image.maxnum, image.maxden), (1, 1));
+            },
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_resolution_bad_length() {
+        match Cursor::new(chunk(b"Reso", &[0x0; 30])).read_chunk() {
+            Err(BlorbError::BadLength{expected: 24, found: 30}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn frontispiece_present() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"not cover"),
+            (b"Pict", 3, b"JPEG", b"cover"),
+        ], &[(b"IFmd", b"<ifindex/>"), (b"Fspc", b"\0\0\0\x03")]));
+
+        match cursor.frontispiece().unwrap() {
+            Some(Chunk::Jpeg{data}) => assert_eq!(data, b"cover"),
+            other => panic!("unexpected frontispiece: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn frontispiece_absent() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[(b"IFmd", b"<ifindex/>")]));
+
+        assert!(cursor.frontispiece().unwrap().is_none());
+    }
+
+    #[test]
+    fn load_chunk_author() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[
+            (b"(c) ", b"Copyright 2016"),
+            (b"AUTH", b"A. Writer"),
+            (b"ANNO", b"Built for testing"),
+        ]));
+
+        match cursor.load_chunk(b"AUTH").unwrap() {
+            Some(Chunk::Author{text}) => assert_eq!(text, "A. Writer"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_chunk(b"(c) ").unwrap() {
+            Some(Chunk::Copyright{text}) => assert_eq!(text, "Copyright 2016"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_chunk(b"ANNO").unwrap() {
+            Some(Chunk::Annotation{text}) =>
+                assert_eq!(text, "Built for testing"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        assert!(cursor.load_chunk(b"RelN").unwrap().is_none());
+    }
+
+    #[test]
+    fn release_number_present() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[(b"AUTH", b"A. Writer"), (b"RelN", b"\0\x2A")]));
+
+        assert_eq!(cursor.release_number().unwrap(), Some(42));
+    }
+
+    #[test]
+    fn release_number_absent() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[]));
+
+        assert_eq!(cursor.release_number().unwrap(), None);
+    }
+
+    #[test]
+    fn executable_kind_glulx() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Exec", 0, b"GLUL", b"glulx code"),
+        ], &[]));
+
+        assert_eq!(cursor.executable_kind().unwrap(), Some(ExecKind::Glulx));
+    }
+
+    #[test]
+    fn executable_kind_data_only() {
+        let mut cursor = open(build_blorb(&[
+            (b"Data", 1, b"TEXT", b"text"),
+        ], &[]));
+
+        assert_eq!(cursor.executable_kind().unwrap(), None);
+    }
+
+    #[test]
+    fn read_aiff_matches_envelope() {
+        let aiff = b"AIFFCOMM\0\0\0\x03\0\x01\x02";
+        let blorb = build_blorb(&[
+            (b"Snd ", 1, b"FORM", aiff),
+            (b"Snd ", 2, b"OGGV", b"ogg"),
+        ], &[]);
+        let mut cursor = open(blorb);
+
+        // the envelope as built by the previous implementation
+        let mut expected = b"FORM".to_vec();
+        expected.extend_from_slice(&[0x0; 0x4]);
+        BigEndian::write_u32(&mut expected[0x4..0x8], aiff.len() as u32);
+        expected.extend_from_slice(b"AIFF");
+        expected.extend_from_slice(&aiff[0x4..]);
+
+        match cursor.load_resource(Usage::Snd, 1).unwrap() {
+            Chunk::Aiff{data} => assert_eq!(data, expected),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.file.read_chunk().unwrap() {
+            Chunk::Ogg{data} => assert_eq!(data, b"ogg"),
+            other => panic!("pad byte not consumed: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn skip_chunk_matches_read() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Snd ", 1, b"XXXX", b"unknown data"),
+        ], &[]));
+
+        for &(usage, num) in &[(Usage::Pict, 1), (Usage::Snd, 1)] {
+            cursor.load_resource(usage, num).unwrap();
+            let read = cursor.file.position();
+
+            let meta = cursor.resource_info(usage, num).unwrap();
+            assert_eq!(cursor.skip_chunk(&meta).unwrap(), read);
+            assert_eq!(cursor.file.position(), read);
+        }
+    }
+
+    #[test]
+    fn chunks_in_file_order() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Snd ", 1, b"OGGV", b"ogg data"),
+        ], &[(b"IFmd", b"<ifindex/>"), (b"Fspc", b"\0\0\0\x01")]));
+
+        let ids: Vec<_> = cursor.chunks()
+            .map(|meta| meta.unwrap().id)
+            .collect();
+        assert_eq!(ids, vec![*b"RIdx", *b"IFmd", *b"Fspc", *b"PNG ", *b"OGGV"]);
+
+        let mut chunks = cursor.chunks();
+        while let Some(meta) = chunks.next() {
+            if &meta.unwrap().id == b"Fspc" {
+                match chunks.load().unwrap() {
+                    Chunk::Frontispiece{num: 1} => (),
+                    other => panic!("unexpected chunk: {:?}", other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn validate_truncated_form() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        blorb.truncate(0x24);
+        let mut cursor = open(blorb);
+
+        match cursor.validate() {
+            Err(BlorbError::BadFormLength{declared: 0x30, actual: 0x24}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn validate_over_declared_form() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        blorb[0x7] += 0x10;
+        let mut cursor = open(blorb);
+
+        match cursor.validate() {
+            Err(BlorbError::BadFormLength{declared: 0x40, actual: 0x30}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn count_per_usage() {
+        let cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Pict", 2, b"JPEG", b"jpeg"),
+            (b"Pict", 5, b"PNG ", b"png"),
+            (b"Snd ", 1, b"OGGV", b"ogg"),
+            (b"Snd ", 2, b"AIFF", b"aiff"),
+            (b"Exec", 0, b"ZCOD", b"zcode"),
+        ], &[]));
+
+        assert_eq!(cursor.count(Usage::Pict), 3);
+        assert_eq!(cursor.count(Usage::Snd), 2);
+        assert_eq!(cursor.count(Usage::Data), 0);
+        assert_eq!(cursor.count(Usage::Exec), 1);
+        assert_eq!(cursor.total_resources(), 6);
+    }
+
+    #[test]
+    fn contains_each_usage() {
+        let cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 2, b"OGGV", b"ogg"),
+            (b"Data", 3, b"TEXT", b"text"),
+        ], &[]));
+
+        assert!(cursor.contains(Usage::Pict, 1));
+        assert!(!cursor.contains(Usage::Pict, 2));
+        assert!(cursor.contains(Usage::Snd, 2));
+        assert!(!cursor.contains(Usage::Snd, 1));
+        assert!(cursor.contains(Usage::Data, 3));
+        assert!(!cursor.contains(Usage::Data, 1));
+        assert!(!cursor.contains(Usage::Exec, 0));
+
+        let cursor = open(build_blorb(&[
+            (b"Exec", 0, b"GLUL", b"glulx"),
+        ], &[]));
+        assert!(cursor.contains(Usage::Exec, 0));
+        assert!(!cursor.contains(Usage::Pict, 0));
+    }
+
+    #[test]
+    fn extract_all_writes_files() {
+        let aiff = b"AIFFCOMM\0\0\0\x02\0\x01";
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Snd ", 3, b"OGGV", b"ogg data"),
+            (b"Snd ", 4, b"FORM", aiff),
+            (b"Exec", 0, b"GLUL", b"glulx"),
+        ], &[]));
+        let dir = ::std::env::temp_dir().join(
+            format!("blorb-extract-{}", ::std::process::id()));
+
+        let paths = cursor.extract_all(&dir).unwrap();
+        let files: Vec<_> = paths.iter()
+            .map(|path| {
+                let name = path.file_name().unwrap().to_str().unwrap();
+                (name.to_string(), ::std::fs::read(path).unwrap())
+            })
+            .collect();
+        ::std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut form = b"FORM\0\0\0\x0E".to_vec();
+        form.extend_from_slice(aiff);
+        assert_eq!(files, vec![
+            ("pict-1.png".to_string(), b"odd".to_vec()),
+            ("snd-3.ogg".to_string(), b"ogg data".to_vec()),
+            ("snd-4.aiff".to_string(), form),
+            ("exec.ulx".to_string(), b"glulx".to_vec()),
+        ]);
+    }
+
+    #[test]
+    fn read_adaptive_palette_three_entries() {
+        let apal = b"\0\0\0\x01\0\0\0\x03\0\0\x01\0";
+        match Cursor::new(chunk(b"APal", apal)).read_chunk().unwrap() {
+            Chunk::AdaptivePalette{entries} =>
+                assert_eq!(entries, vec![1, 3, 256]),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_adaptive_palette_bad_length() {
+        match Cursor::new(chunk(b"APal", &[0x0; 6])).read_chunk() {
+            Err(BlorbError::BadLength{expected: 4, found: 6}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_palette_two_colors() {
+        let plte = chunk(b"Plte", &[0xFF, 0x0, 0x0, 0x10, 0x20, 0x30]);
+        match Cursor::new(plte).read_chunk().unwrap() {
+            Chunk::Palette{colors} => assert_eq!(colors, vec![
+                (0xFF, 0x0, 0x0),
+                (0x10, 0x20, 0x30),
+            ]),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_palette_bad_length() {
+        match Cursor::new(chunk(b"Plte", &[0x0; 4])).read_chunk() {
+            Err(BlorbError::BadLength{expected: 3, found: 4}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_palette_empty() {
+        match Cursor::new(chunk(b"Plte", &[])).read_chunk() {
+            Err(BlorbError::BadLength{expected: 3, found: 0}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn read_palette_direct_color() {
+        for &depth in &[16, 32] {
+            let plte = chunk(b"Plte", &[depth]);
+            match Cursor::new(plte).read_chunk().unwrap() {
+                Chunk::DirectPalette{depth: found} => assert_eq!(found, depth),
+                other => panic!("unexpected chunk: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn read_palette_bad_depth() {
+        match Cursor::new(chunk(b"Plte", &[24])).read_chunk() {
+            Err(BlorbError::BadPaletteDepth{depth: 24}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn chunk_id_round_trips() {
+        let ids: &[&[u8; 0x4]] = &[
+            b"(c) ", b"ADRI", b"ADVS", b"AGT ", b"ALAN", b"ANNO", b"APal",
+            b"AUTH", b"BINA", b"Dbug", b"EXEC", b"Fspc", b"GIF ", b"GLUL",
+            b"HUGO", b"IFmd", b"JPEG", b"LEVE", b"MAGS", b"MIDI", b"MOD ",
+            b"MP3 ", b"OGGV", b"PNG ", b"Plte", b"RIdx", b"Rect", b"RelN",
+            b"Reso", b"SNam", b"SONG", b"TAD2", b"TAD3", b"TEXT", b"WAV ",
+            b"ZCOD", b"XXXX",
+        ];
+        for &id in ids {
+            let body: &[u8] = match id {
+                b"RIdx" | b"Fspc" | b"APal" => &[0x0; 4],
+                b"RelN" => &[0x0; 2],
+                b"Plte" => &[0x0; 3],
+                b"Rect" => &[0x0; 8],
+                b"Reso" => &[0x0; 24],
+                _ => b"data",
+            };
+            let chunk = Cursor::new(chunk(id, body)).read_chunk().unwrap();
+            assert_eq!(&chunk.id(), id);
+        }
+
+        let aiff = Cursor::new(chunk(b"FORM", b"AIFF")).read_chunk().unwrap();
+        assert_eq!(&aiff.id(), b"AIFF");
+        let form = Cursor::new(chunk(b"FORM", b"XXXX")).read_chunk().unwrap();
+        assert_eq!(&form.id(), b"XXXX");
+    }
+
+    #[test]
+    fn load_resource_raw_png() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Pict", 2, b"XXXX", b"unknown"),
+        ], &[]));
+
+        let (meta, data) = cursor.load_resource_raw(Usage::Pict, 1).unwrap();
+        assert_eq!(&meta.id, b"PNG ");
+        assert_eq!(meta.len, 3);
+        match cursor.load_resource(Usage::Pict, 1).unwrap() {
+            Chunk::Png{data: loaded} => assert_eq!(data, loaded),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+
+        let (meta, data) = cursor.load_resource_raw(Usage::Pict, 2).unwrap();
+        assert_eq!(&meta.id, b"XXXX");
+        assert_eq!(data, b"unknown");
+    }
+
+    #[test]
+    fn blorb_load_eagerly() {
+        let blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Snd ", 3, b"OGGV", b"ogg"),
+            (b"Data", 4, b"TEXT", b"text"),
+            (b"Exec", 0, b"GLUL", b"glulx"),
+        ], &[
+            (b"IFmd", b"<ifindex/>"),
+            (b"Fspc", b"\0\0\0\x01"),
+            (b"AUTH", b"A. Writer"),
+        ]);
+
+        // a slice is read without seeking
+        let blorb = Blorb::load(&blorb[..]).unwrap();
+        assert_eq!(blorb.index.entries().count(), 4);
+        match blorb.pictures[&1] {
+            Chunk::Png{ref data} => assert_eq!(data, b"odd"),
+            ref other => panic!("unexpected chunk: {:?}", other),
+        }
+        match blorb.sounds[&3] {
+            Chunk::Ogg{ref data} => assert_eq!(data, b"ogg"),
+            ref other => panic!("unexpected chunk: {:?}", other),
+        }
+        match blorb.data[&4] {
+            Chunk::Text{ref text} => assert_eq!(text, "text"),
+            ref other => panic!("unexpected chunk: {:?}", other),
+        }
+        match blorb.exec {
+            Some(Chunk::Glulx{ref code}) => assert_eq!(code, b"glulx"),
+            ref other => panic!("unexpected chunk: {:?}", other),
+        }
+        assert_eq!(blorb.metadata, Some("<ifindex/>".to_string()));
+        assert_eq!(blorb.frontispiece, Some(1));
+        match blorb.chunks[..] {
+            [Chunk::Author{ref text}] => assert_eq!(text, "A. Writer"),
+            ref other => panic!("unexpected chunks: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn blorb_load_bad_offset() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        blorb[0x23] += 0x2;
+
+        match Blorb::load(&blorb[..]) {
+            Err(BlorbError::BadOffset{usage: Usage::Pict, num: 1, ..}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn blorb_load_shared_chunk() {
+        let mut blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Pict", 2, b"PNG ", b"other"),
+        ], &[(b"AUTH", b"A. Writer")]);
+        // point the second entry at the chunk of the first
+        let start = blorb[0x20..0x24].to_vec();
+        blorb[0x2C..0x30].copy_from_slice(&start);
+
+        let blorb = Blorb::load(&blorb[..]).unwrap();
+        assert_eq!(blorb.pictures[&1], Chunk::Png{data: b"png".to_vec()});
+        assert_eq!(blorb.pictures[&2], Chunk::Png{data: b"png".to_vec()});
+        // the chunk no longer referenced is kept with the other chunks
+        assert_eq!(blorb.chunks, vec![
+            Chunk::Author{text: "A. Writer".to_string()},
+            Chunk::Png{data: b"other".to_vec()},
+        ]);
+    }
+
+    #[test]
+    fn debug_info_present() {
+        let mut cursor = open(build_blorb(&[
+            (b"Exec", 0, b"ZCOD", b"zcode"),
+        ], &[(b"Dbug", &[0x1; 0x15])]));
+
+        let data = cursor.debug_info().unwrap().unwrap();
+        assert_eq!(data.len(), 0x15);
+    }
+
+    #[test]
+    fn read_resource_index_overflowing_num() {
+        let ridx = chunk(b"RIdx", &[0xFF, 0xFF, 0xFF, 0xFF]);
+        match Cursor::new(ridx).read_chunk() {
+            Err(BlorbError::BadIndexLength{
+                num: 0xFFFFFFFF,
+                expected: 0xBFFFFFFF8,
+                found: 4,
+            }) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn load_resource_borrowed_aliases_buffer() {
+        let blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Snd ", 2, b"OGGV", b"ogg data"),
+        ], &[]);
+        let cursor = BlorbCursor::from_bytes(&blorb[..]).unwrap();
+
+        let (meta, data) =
+            cursor.load_resource_borrowed(Usage::Snd, 2).unwrap();
+        assert_eq!(&meta.id, b"OGGV");
+        assert_eq!(data, b"ogg data");
+        let offset = blorb.len() - data.len();
+        assert_eq!(data.as_ptr(), blorb[offset..].as_ptr());
+
+        match cursor.load_resource_borrowed(Usage::Pict, 2) {
+            Err(BlorbError::NotFound) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn load_resource_borrowed_truncated() {
+        let mut blorb = build_blorb(&[(b"Pict", 1, b"PNG ", b"png data")], &[]);
+        let len = blorb.len();
+        blorb.truncate(len - 0x2);
+        let cursor = BlorbCursor::from_bytes(blorb).unwrap();
+
+        match cursor.load_resource_borrowed(Usage::Pict, 1) {
+            Err(BlorbError::Io(ref err))
+                if err.kind() == ErrorKind::UnexpectedEof => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_file_with_options_skips_leading_junk() {
+        let mut blorb = b"junk".to_vec();
+        blorb.append(&mut build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+        ], &[(b"Fspc", b"\0\0\0\x01")]));
+
+        match BlorbCursor::from_bytes(blorb.clone()).err() {
+            Some(BlorbError::NotBlorb) => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let options = BlorbOptions{
+            skip_leading_junk: true,
+            ..BlorbOptions::default()
+        };
+        let mut cursor = BlorbCursor::from_file_with_options(
+            Cursor::new(blorb), options).unwrap();
+        cursor.validate().unwrap();
+        match cursor.load_resource(Usage::Pict, 1).unwrap() {
+            Chunk::Png{data} => assert_eq!(data, b"odd"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        assert!(cursor.frontispiece().unwrap().is_some());
+        let (_, data) = cursor.load_resource_borrowed(Usage::Pict, 1).unwrap();
+        assert_eq!(data, b"odd");
+    }
+
+    #[test]
+    fn resource_range_matches_fixture() {
+        let blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Snd ", 2, b"OGGV", b"ogg data"),
+        ], &[]);
+        let mut cursor = open(blorb.clone());
+
+        let (start, len) = cursor.resource_range(Usage::Snd, 2).unwrap();
+        assert_eq!((start, len), (blorb.len() as u64 - 8, 8));
+        let range = start as usize..start as usize + len as usize;
+        assert_eq!(&blorb[range], b"ogg data");
+    }
+
+    #[test]
+    fn load_resource_equal_chunks() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Pict", 2, b"PNG ", b"png"),
+            (b"Pict", 3, b"PNG ", b"other"),
+        ], &[]));
+
+        let first = cursor.load_resource(Usage::Pict, 1).unwrap();
+        assert_eq!(first, cursor.load_resource(Usage::Pict, 2).unwrap());
+        assert!(first != cursor.load_resource(Usage::Pict, 3).unwrap());
+    }
+
+    #[test]
+    fn validate_exec_not_executable() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Exec", 0, b"PNG ", b"png"),
+        ], &[]));
+
+        match cursor.validate() {
+            Err(BlorbError::NotExecutable{id}) => assert_eq!(&id, b"PNG "),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn load_resource_odd_final_chunk_padded() {
+        let blorb = build_blorb(&[(b"Data", 1, b"TEXT", b"odd")], &[]);
+        assert_eq!(blorb.len() & 1, 0);
+
+        let mut cursor = open(blorb.clone());
+        match cursor.load_resource(Usage::Data, 1).unwrap() {
+            Chunk::Text{text} => assert_eq!(text, "odd"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        assert_eq!(Blorb::load(Cursor::new(blorb)).unwrap().data.len(), 1);
+    }
+
+    #[test]
+    fn load_resource_odd_final_chunk_unpadded() {
+        let mut blorb = build_blorb(&[(b"Data", 1, b"TEXT", b"odd")], &[]);
+        blorb.pop();
+        let len = blorb.len() as u32 - 0x8;
+        BigEndian::write_u32(&mut blorb[0x4..0x8], len);
+
+        let mut cursor = open(blorb.clone());
+        match cursor.load_resource(Usage::Data, 1).unwrap() {
+            Chunk::Text{text} => assert_eq!(text, "odd"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        cursor.validate().unwrap();
+        assert_eq!(Blorb::load(Cursor::new(blorb)).unwrap().data.len(), 1);
+    }
+
+    #[test]
+    fn metadata_present() {
+        let xml = "<ifindex><story><bibliographic><title>Title</title>\
+                   </bibliographic></story></ifindex>";
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[(b"RelN", b"\0\x01"), (b"IFmd", xml.as_bytes())]));
+        assert_eq!(cursor.metadata().unwrap(), Some(xml.to_string()));
+    }
+
+    #[test]
+    fn metadata_absent() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[(b"RelN", b"\0\x01")]));
+        assert_eq!(cursor.metadata().unwrap(), None);
+    }
+
+    #[test]
+    fn declared_count_matches_entries() {
+        let cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 1, b"OGGV", b"ogg"),
+        ], &[]));
+        assert_eq!(cursor.declared_count(), 2);
+        assert_eq!(cursor.total_resources(), 2);
+    }
+
+    #[test]
+    fn declared_count_exceeds_entries() {
+        let valid = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 1, b"OGGV", b"ogg"),
+        ], &[]);
+        // a header declaring more entries than the index holds does not
+        // match the length of the index chunk
+        let mut blorb = valid.clone();
+        BigEndian::write_u32(&mut blorb[0x14..0x18], 3);
+        match BlorbCursor::from_bytes(blorb).err() {
+            Some(BlorbError::BadIndexLength{num: 3, expected: 40, found: 28})
+                => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+        assert_eq!(open(valid).declared_count(), 2);
+    }
+
+    #[test]
+    fn load_executable_glulx() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Exec", 0, b"GLUL", b"glulx code"),
+        ], &[]));
+
+        assert_eq!(
+            cursor.load_executable().unwrap(),
+            Some(Chunk::Glulx{code: b"glulx code".to_vec()}));
+    }
+
+    #[test]
+    fn load_executable_data_only() {
+        let mut cursor = open(build_blorb(&[
+            (b"Data", 1, b"TEXT", b"text"),
+        ], &[]));
+
+        assert_eq!(cursor.load_executable().unwrap(), None);
+    }
+
+    #[test]
+    fn read_short_name_utf16() {
+        let name = b"\0Z\0o\0r\0k\x20\x22";
+        let chunk = Cursor::new(chunk(b"SNam", name)).read_chunk().unwrap();
+        assert_eq!(chunk, Chunk::ShortName{name: "Zork\u{2022}".to_string()});
+    }
+
+    #[test]
+    fn read_short_name_invalid() {
+        // an unpaired surrogate, and a trailing odd byte
+        let name = b"\0Z\xD8\0\0k\0";
+        let chunk = Cursor::new(chunk(b"SNam", name)).read_chunk().unwrap();
+        assert_eq!(
+            chunk,
+            Chunk::ShortName{name: "Z\u{FFFD}k\u{FFFD}".to_string()});
+    }
+
+    #[test]
+    fn reload_after_file_changed() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[]));
+
+        let changed = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"new png"),
+            (b"Snd ", 1, b"OGGV", b"ogg"),
+        ], &[]);
+        let len = changed.len() as u32 - 0x8;
+        *cursor.file.get_mut() = changed;
+        assert!(!cursor.contains(Usage::Snd, 1));
+
+        cursor.reload().unwrap();
+        assert_eq!(cursor.len, len);
+        assert!(cursor.contains(Usage::Snd, 1));
+        assert_eq!(
+            cursor.load_resource(Usage::Pict, 1).unwrap(),
+            Chunk::Png{data: b"new png".to_vec()});
+    }
+
+    #[test]
+    fn reload_not_blorb() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[]));
+
+        cursor.file.get_mut()[0x8..0xC].copy_from_slice(b"AIFF");
+        match cursor.reload() {
+            Err(BlorbError::NotBlorb) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(cursor.contains(Usage::Pict, 1));
+    }
+
+    #[test]
+    fn reload_too_short_for_blorb() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[]));
+
+        cursor.file.get_mut().truncate(0x5);
+        match cursor.reload() {
+            Err(BlorbError::TooShortForBlorb{available: 5}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(cursor.contains(Usage::Pict, 1));
+    }
+
+    #[test]
+    fn reload_keeps_options() {
+        let valid = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Pict", 2, b"PNG ", b"png"),
+        ], &[]);
+        let options = BlorbOptions{
+            reject_duplicate_entries: true,
+            ..BlorbOptions::default()
+        };
+        let mut cursor = BlorbCursor::from_file_with_options(
+            Cursor::new(valid.clone()), options).unwrap();
+
+        *cursor.file.get_mut() =
+            corrupt_blorb(&valid, Corruption::DuplicateEntry);
+        match cursor.reload() {
+            Err(BlorbError::DuplicateEntry{usage: Usage::Pict, num: 1}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(cursor.declared_count(), 2);
+    }
+
+    #[test]
+    fn load_entry_matches_load_resource() {
+        let mut cursor = open(build_blorb(&[
+            (b"Exec", 0, b"ZCOD", b"zcode"),
+            (b"Snd ", 4, b"OGGV", b"ogg"),
+            (b"Pict", 3, b"PNG ", b"png"),
+            (b"Data", 5, b"TEXT", b"text"),
+        ], &[]));
+
+        let entries: Vec<IndexEntry> = cursor.entries().cloned().collect();
+        assert_eq!(entries.len(), 4);
+        for entry in &entries {
+            assert_eq!(
+                cursor.load_entry(entry).unwrap(),
+                cursor.load_resource(entry.usage, entry.num).unwrap());
+        }
+    }
+
+    fn zcode_story() -> Vec<u8> {
+        let mut story = vec![0x0; 0x40];
+        story[0x0] = 5;
+        story[0x3] = 3;
+        story[0x12..0x18].copy_from_slice(b"960112");
+        story[0x1C..0x1E].copy_from_slice(&[0xAB, 0xCD]);
+        story
+    }
+
+    fn ifid_metadata(ifid: &str) -> String {
+        format!("<ifindex><story><identification><ifid>{}</ifid>\
+                 </identification></story></ifindex>", ifid)
+    }
+
+    #[test]
+    fn verify_ifid_matches() {
+        let xml = ifid_metadata("ZCODE-3-960112-ABCD");
+        let mut cursor = open(build_blorb(&[
+            (b"Exec", 0, b"ZCOD", &zcode_story()),
+        ], &[(b"IFmd", xml.as_bytes())]));
+        assert!(cursor.verify_ifid().unwrap());
+    }
+
+    #[test]
+    fn verify_ifid_mismatch() {
+        let xml = ifid_metadata("ZCODE-2-960112-ABCD");
+        let mut cursor = open(build_blorb(&[
+            (b"Exec", 0, b"ZCOD", &zcode_story()),
+        ], &[(b"IFmd", xml.as_bytes())]));
+        assert!(!cursor.verify_ifid().unwrap());
+    }
+
+    #[test]
+    fn verify_ifid_unavailable() {
+        let xml = ifid_metadata("ZCODE-3-960112-ABCD");
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[(b"IFmd", xml.as_bytes())]));
+        match cursor.verify_ifid() {
+            Err(BlorbError::IfidUnavailable(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let mut cursor = open(build_blorb(&[
+            (b"Exec", 0, b"ZCOD", &zcode_story()),
+        ], &[]));
+        match cursor.verify_ifid() {
+            Err(BlorbError::IfidUnavailable(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn entries_of_pictures() {
+        let cursor = open(build_blorb(&[
+            (b"Pict", 7, b"PNG ", b"png"),
+            (b"Exec", 0, b"ZCOD", b"zcode"),
+            (b"Snd ", 4, b"OGGV", b"ogg"),
+            (b"Pict", 3, b"PNG ", b"png"),
+            (b"Data", 5, b"TEXT", b"text"),
+            (b"Pict", 1, b"JPEG", b"jpeg"),
+        ], &[]));
+
+        let pictures: Vec<_> = cursor.entries_of(Usage::Pict)
+            .map(|entry| (entry.usage, entry.num))
+            .collect();
+        assert_eq!(pictures, vec![
+            (Usage::Pict, 1), (Usage::Pict, 3), (Usage::Pict, 7),
+        ]);
+        assert_eq!(cursor.entries_of(Usage::Exec).count(), 1);
+    }
+
+    #[test]
+    fn from_file_with_options_rejects_duplicate_entries() {
+        let valid = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Pict", 2, b"PNG ", b"png"),
+        ], &[]);
+        let blorb = corrupt_blorb(&valid, Corruption::DuplicateEntry);
+
+        // the default options keep the last entry
+        let cursor = open(blorb.clone());
+        assert_eq!(cursor.total_resources(), 2);
+
+        let options = BlorbOptions{
+            reject_duplicate_entries: true,
+            ..BlorbOptions::default()
+        };
+        let result = BlorbCursor::from_file_with_options(
+            Cursor::new(blorb), options);
+        match result {
+            Err(BlorbError::DuplicateEntry{usage: Usage::Pict, num: 1}) => (),
+            Err(err) => panic!("unexpected error: {:?}", err),
+            Ok(_) => panic!("duplicate entry was not rejected"),
+        }
+
+        let cursor = BlorbCursor::from_file_with_options(
+            Cursor::new(valid), options).unwrap();
+        assert_eq!(cursor.declared_count(), 2);
+    }
+
+    #[test]
+    fn from_file_with_options_rejects_second_exec() {
+        let blorb = build_blorb(&[
+            (b"Exec", 0, b"ZCOD", b"zcode"),
+            (b"Exec", 1, b"GLUL", b"glulx"),
+        ], &[]);
+        let options = BlorbOptions{
+            reject_duplicate_entries: true,
+            ..BlorbOptions::default()
+        };
+        let result = BlorbCursor::from_file_with_options(
+            Cursor::new(blorb), options);
+        match result {
+            Err(BlorbError::DuplicateEntry{usage: Usage::Exec, num: 1}) => (),
+            Err(err) => panic!("unexpected error: {:?}", err),
+            Ok(_) => panic!("second executable was not rejected"),
+        }
+    }
+
+    #[test]
+    fn file_size_matches_fixture() {
+        let blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+            (b"Snd ", 1, b"OGGV", b"ogg data"),
+        ], &[(b"AUTH", b"A. Writer")]);
+        let len = blorb.len() as u64;
+        assert_eq!(open(blorb).file_size(), len);
+    }
+
+    #[test]
+    fn read_text_utf8() {
+        let text = "caf\u{E9} \u{2022}";
+        let chunk = Cursor::new(chunk(b"TEXT", text.as_bytes()))
+            .read_chunk().unwrap();
+        assert_eq!(chunk, Chunk::Text{text: text.to_string()});
+    }
+
+    #[test]
+    fn read_text_latin1() {
+        let text = Cursor::new(chunk(b"TEXT", b"caf\xE9"))
+            .read_chunk().unwrap();
+        assert_eq!(text, Chunk::Text{text: "caf\u{E9}".to_string()});
+
+        let info = Cursor::new(chunk(b"IFmd", b"<title>Caf\xE9</title>"))
+            .read_chunk().unwrap();
+        assert_eq!(
+            info,
+            Chunk::Metadata{info: "<title>Caf\u{E9}</title>".to_string()});
+    }
+
+    #[test]
+    fn read_attribution_latin1() {
+        let author = Cursor::new(chunk(b"AUTH", b"Andr\xE9"))
+            .read_chunk().unwrap();
+        assert_eq!(author, Chunk::Author{text: "Andr\u{E9}".to_string()});
+
+        let copyright = Cursor::new(chunk(b"(c) ", b"\xA9 2001"))
+            .read_chunk().unwrap();
+        assert_eq!(
+            copyright,
+            Chunk::Copyright{text: "\u{A9} 2001".to_string()});
+
+        let annotation = Cursor::new(chunk(b"ANNO", b"na\xEFve"))
+            .read_chunk().unwrap();
+        assert_eq!(
+            annotation,
+            Chunk::Annotation{text: "na\u{EF}ve".to_string()});
+    }
+
+    #[test]
+    fn read_text_empty() {
+        let chunk = Cursor::new(chunk(b"TEXT", b"")).read_chunk().unwrap();
+        assert_eq!(chunk, Chunk::Text{text: String::new()});
+    }
+
+    #[test]
+    fn cover_art_png() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"JPEG", b"not cover"),
+            (b"Pict", 2, b"PNG ", b"cover"),
+        ], &[(b"Fspc", b"\0\0\0\x02")]));
+
+        assert_eq!(
+            cursor.cover_art().unwrap(),
+            Some((ImageFormat::Png, b"cover".to_vec())));
+    }
+
+    #[test]
+    fn cover_art_absent() {
+        let mut cursor = open(build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png"),
+        ], &[(b"IFmd", b"<ifindex/>")]));
+
+        assert_eq!(cursor.cover_art().unwrap(), None);
+    }
+
+    #[test]
+    fn cover_art_missing_picture() {
+        let valid = build_blorb(&[(b"Pict", 1, b"PNG ", b"png")], &[]);
+        let mut cursor = open(corrupt_blorb(&valid, Corruption::DanglingFspc));
+
+        match cursor.cover_art() {
+            Err(BlorbError::NotFound) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -8,21 +8,48 @@
 //! along with executable code, for IF interpreters to use.
 //!
 //! This library gives access to the blorb file type and contents
-//! through structures. Additionally, it provides a lazy access
-//! interface to the blorb contents, allowing interpreters to use blorbs
+//! through structures. A blorb can be loaded into memory in full as a
+//! `Blorb`, or built and written out using a `BlorbWriter`.
+//! Additionally, it provides a lazy access interface to the blorb
+//! contents through `BlorbCursor`, allowing interpreters to use blorbs
 //! without dumping the full file contents contents into memory.
 //!
 //! **NOTE**: This library is not production ready. The interface is
-//! currently unstable, and only the lazy-loading portion of this
-//! library has been implemented.
+//! currently unstable.
 
 extern crate byteorder;
+#[cfg(feature = "decoding")]
+extern crate hound;
+#[cfg(feature = "decoding")]
+extern crate lewton;
+#[cfg(feature = "decoding")]
+extern crate minimp3;
+#[cfg(any(test, feature = "async"))]
+extern crate tokio;
 
+#[cfg(feature = "async")]
+mod async_io;
 mod blorb;
+mod cache;
+#[cfg(feature = "decoding")]
+mod decode;
+mod error;
 mod io;
+mod metadata;
+#[cfg(any(test, feature = "testutil"))]
+pub mod testutil;
+mod write;
 
+#[cfg(feature = "async")]
+pub use async_io::*;
 pub use blorb::*;
+pub use cache::*;
+#[cfg(feature = "decoding")]
+pub use decode::*;
+pub use error::*;
 pub use io::*;
+pub use metadata::*;
+pub use write::*;
 
 #[cfg(test)]
 mod tests {
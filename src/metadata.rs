@@ -0,0 +1,223 @@
+use crate::error::{
+    BlorbError,
+    Result,
+};
+
+
+/// Common bibliographic fields of the iFiction metadata held in an
+/// `IFmd` chunk.
+///
+/// Only the `<identification>` and `<bibliographic>` sections of the
+/// metadata are examined. Fields missing from the metadata are left as
+/// `None`, or empty for `ifids`.
+#[derive(Clone, Debug, Default)]
+pub struct IfMetadata {
+    /// The IFIDs identifying the story
+    pub ifids: Vec<String>,
+    /// The title of the story
+    pub title: Option<String>,
+    /// The author or authors of the story
+    pub author: Option<String>,
+    /// The headline, or subtitle, of the story
+    pub headline: Option<String>,
+    /// The date the story was first published
+    pub firstpublished: Option<String>,
+    /// A description of the story. Paragraph breaks are given as
+    /// newlines.
+    pub description: Option<String>,
+}
+
+
+impl IfMetadata {
+
+    /// Parses the iFiction XML metadata, as held in `Chunk::Metadata`,
+    /// into an `IfMetadata`. This is a lightweight parser, which only
+    /// looks for the elements of interest rather than validating the
+    /// full document. A `BlorbError::BadMetadata` is returned if the
+    /// document is not iFiction metadata, or if an element of interest
+    /// is not closed.
+    pub fn parse(xml: &str) -> Result<IfMetadata> {
+        if !xml.contains("<ifindex") {
+            return Err(BlorbError::BadMetadata(
+                "missing ifindex element".to_string()));
+        }
+
+        let mut metadata = IfMetadata::default();
+        for identification in elements(xml, "identification")? {
+            for ifid in elements(identification, "ifid")? {
+                metadata.ifids.push(text(ifid));
+            }
+        }
+        if let Some(bibliographic) = elements(xml, "bibliographic")?.first() {
+            let field = |tag| -> Result<Option<String>> {
+                let values = elements(bibliographic, tag)?;
+                Ok(values.first().map(|value| text(value)))
+            };
+            metadata.title = field("title")?;
+            metadata.author = field("author")?;
+            metadata.headline = field("headline")?;
+            metadata.firstpublished = field("firstpublished")?;
+            metadata.description = field("description")?;
+        }
+        Ok(metadata)
+    }
+}
+
+
+/// Returns the contents of every element with the given tag name in
+/// the xml, in document order. Elements with the same tag name are
+/// not expected to nest.
+fn elements<'a>(xml: &'a str, tag: &str) -> Result<Vec<&'a str>> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        rest = &rest[start + open.len()..];
+
+        // skip elements which only share a prefix with the tag name
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        match rest[..end].chars().next() {
+            Some(c) if c.is_whitespace() || c == '/' => (),
+            None => (),
+            _ => continue,
+        }
+
+        if rest[..end].ends_with('/') {
+            found.push("");
+            rest = &rest[end + 1..];
+            continue;
+        }
+        rest = &rest[end + 1..];
+        match rest.find(&close) {
+            Some(len) => {
+                found.push(&rest[..len]);
+                rest = &rest[len + close.len()..];
+            },
+            None => return Err(BlorbError::BadMetadata(
+                format!("{} element not closed", tag))),
+        }
+    }
+    Ok(found)
+}
+
+
+/// Converts the contents of an element into text. Entities are
+/// decoded, runs of whitespace are collapsed into single spaces, and
+/// `<br/>` elements are converted into newlines.
+fn text(contents: &str) -> String {
+    let lines: Vec<String> = contents
+        .replace("<br />", "<br/>")
+        .split("<br/>")
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .map(|line| decode_entities(&line))
+        .collect();
+    lines.join("\n")
+}
+
+
+/// Decodes the predefined xml entities, and numeric character
+/// references. Unrecognized entities are left as is.
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| (&rest[1..end], end));
+        let c = entity.and_then(|(name, _)| match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ if name.starts_with("#x") => u32::from_str_radix(&name[2..], 16)
+                .ok().and_then(char::from_u32),
+            _ if name.starts_with('#') => name[1..].parse()
+                .ok().and_then(char::from_u32),
+            _ => None,
+        });
+        match (c, entity) {
+            (Some(c), Some((_, end))) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            },
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ifindex version="1.0" xmlns="http://babel.ifarchive.org/protocol/iFiction/">
+  <story>
+    <identification>
+      <ifid>ZCODE-3-880727-B53E</ifid>
+      <ifid>6B1C8A1E-0B8C-4C5A-9F1E-4A0A0E0A5B7C</ifid>
+      <format>zcode</format>
+    </identification>
+    <bibliographic>
+      <title>The  Example &amp; Its Sequel</title>
+      <author>A. Writer</author>
+      <language>en</language>
+      <firstpublished>1988</firstpublished>
+      <description>A story in
+        two parts.<br/>The second &#x201C;part&#8221;.</description>
+    </bibliographic>
+  </story>
+</ifindex>"#;
+
+    #[test]
+    fn parse_metadata() {
+        let metadata = IfMetadata::parse(METADATA).unwrap();
+        assert_eq!(metadata.ifids, vec![
+            "ZCODE-3-880727-B53E",
+            "6B1C8A1E-0B8C-4C5A-9F1E-4A0A0E0A5B7C",
+        ]);
+        assert_eq!(metadata.title.unwrap(), "The Example & Its Sequel");
+        assert_eq!(metadata.author.unwrap(), "A. Writer");
+        assert_eq!(metadata.headline, None);
+        assert_eq!(metadata.firstpublished.unwrap(), "1988");
+        assert_eq!(metadata.description.unwrap(),
+            "A story in two parts.\nThe second \u{201C}part\u{201D}.");
+    }
+
+    #[test]
+    fn parse_metadata_missing_sections() {
+        let xml = "<ifindex><story/></ifindex>";
+        let metadata = IfMetadata::parse(xml).unwrap();
+        assert!(metadata.ifids.is_empty());
+        assert_eq!(metadata.title, None);
+    }
+
+    #[test]
+    fn parse_metadata_unclosed() {
+        let xml = "<ifindex><bibliographic><title>Example</bibliographic>";
+        match IfMetadata::parse(xml) {
+            Err(BlorbError::BadMetadata(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_metadata_not_ifindex() {
+        match IfMetadata::parse("<html></html>") {
+            Err(BlorbError::BadMetadata(_)) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use byteorder::{
+    BigEndian,
+    ByteOrder,
+};
+
+
+/// The kinds of corruption `corrupt_blorb` can apply to a valid blorb.
+#[derive(Clone, Copy, Debug)]
+pub enum Corruption {
+
+    /// Truncates the blorb directly after the resource index, leaving
+    /// the declared form length larger than the data available.
+    TruncateForm,
+
+    /// Increases the declared length of the resource index chunk so it
+    /// no longer matches the number of index entries.
+    BadIndexLen,
+
+    /// Appends a `Fspc` chunk referencing a picture resource which is
+    /// not present in the resource index.
+    DanglingFspc,
+
+    /// Duplicates the first resource index entry, so two entries share
+    /// the same usage and resource number.
+    DuplicateEntry,
+
+    /// Points the first resource index entry past the end of the blorb.
+    OffsetPastEnd,
+}
+
+
+/// Applies the given `Corruption` to a copy of a valid blorb, and
+/// returns the corrupted blorb. The valid blorb is expected to begin
+/// with the `FORM` header, followed by the `RIdx` chunk.
+///
+/// # Panics
+///
+/// Panics if `valid` is too short to hold a resource index, or if the
+/// corruption requires an index entry and the resource index is empty.
+pub fn corrupt_blorb(valid: &[u8], kind: Corruption) -> Vec<u8> {
+    let mut blorb = valid.to_vec();
+    let num = BigEndian::read_u32(&blorb[0x14..0x18]) as usize;
+    let entries = 0x18;
+    let index_end = entries + num * 0xC;
+
+    match kind {
+        Corruption::TruncateForm => blorb.truncate(index_end),
+        Corruption::BadIndexLen => {
+            let len = BigEndian::read_u32(&blorb[0x10..0x14]);
+            BigEndian::write_u32(&mut blorb[0x10..0x14], len + 0x1);
+        },
+        Corruption::DanglingFspc => {
+            let dangling = (0..num)
+                .map(|i| &blorb[entries + i * 0xC..entries + (i + 1) * 0xC])
+                .filter(|entry| &entry[0x0..0x4] == b"Pict")
+                .map(|entry| BigEndian::read_u32(&entry[0x4..0x8]) + 1)
+                .max()
+                .unwrap_or(0);
+
+            let mut fspc = [0x0; 0xC];
+            fspc[0x0..0x4].copy_from_slice(b"Fspc");
+            BigEndian::write_u32(&mut fspc[0x4..0x8], 0x4);
+            BigEndian::write_u32(&mut fspc[0x8..0xC], dangling);
+            blorb.extend_from_slice(&fspc);
+            set_form_len(&mut blorb);
+        },
+        Corruption::DuplicateEntry => {
+            assert!(num > 0, "resource index has no entry to duplicate");
+            let entry = blorb[entries..entries + 0xC].to_vec();
+            blorb.splice(index_end..index_end, entry);
+            let num = num + 1;
+            BigEndian::write_u32(&mut blorb[0x14..0x18], num as u32);
+            let len = num as u32 * 0xC + 0x4;
+            BigEndian::write_u32(&mut blorb[0x10..0x14], len);
+
+            // every chunk after the index has moved by one entry
+            for i in 0..num {
+                let start = &mut blorb[entries + i * 0xC + 0x8..][..0x4];
+                let offset = BigEndian::read_u32(start);
+                BigEndian::write_u32(start, offset + 0xC);
+            }
+            set_form_len(&mut blorb);
+        },
+        Corruption::OffsetPastEnd => {
+            assert!(num > 0, "resource index has no entry to corrupt");
+            let len = blorb.len() as u32;
+            BigEndian::write_u32(&mut blorb[entries + 0x8..entries + 0xC], len);
+        },
+    }
+    blorb
+}
+
+
+/// Updates the `FORM` length of the blorb to match its data.
+fn set_form_len(blorb: &mut [u8]) {
+    let len = blorb.len() as u32 - 0x8;
+    BigEndian::write_u32(&mut blorb[0x4..0x8], len);
+}
+
+
+/// Builds a chunk with the given identifier and body, including the
+/// pad byte for odd length bodies.
+#[cfg(any(test, feature = "testutil"))]
+pub fn chunk(id: &[u8; 0x4], body: &[u8]) -> Vec<u8> {
+    let mut chunk = Vec::with_capacity(body.len() + 0x9);
+    chunk.extend_from_slice(id);
+    chunk.extend_from_slice(&[0x0; 0x4]);
+    BigEndian::write_u32(&mut chunk[0x4..0x8], body.len() as u32);
+    chunk.extend_from_slice(body);
+    if body.len() & 1 == 1 {chunk.push(0x0)};
+    chunk
+}
+
+
+/// A resource for `build_blorb`, given as the usage identifier,
+/// resource number, chunk identifier, and chunk body of the resource.
+#[cfg(any(test, feature = "testutil"))]
+pub type Resource<'a> = (&'a [u8; 0x4], u32, &'a [u8; 0x4], &'a [u8]);
+
+
+/// Builds a valid blorb for tests from the given resources. `chunks`
+/// are placed directly after the resource index, and are given as the
+/// chunk identifier and chunk body.
+#[cfg(any(test, feature = "testutil"))]
+pub fn build_blorb(resources: &[Resource], chunks: &[(&[u8; 0x4], &[u8])])
+        -> Vec<u8> {
+    let mut index = vec![0x0; 0x4 + resources.len() * 0xC];
+    BigEndian::write_u32(&mut index[0x0..0x4], resources.len() as u32);
+
+    let mut body = Vec::new();
+    for &(id, data) in chunks {
+        body.append(&mut chunk(id, data));
+    }
+
+    let mut start = 0xC + 0x8 + index.len() + body.len();
+    for (i, &(usage, num, id, data)) in resources.iter().enumerate() {
+        let entry = &mut index[0x4 + i * 0xC..0x4 + (i + 1) * 0xC];
+        entry[0x0..0x4].copy_from_slice(usage);
+        BigEndian::write_u32(&mut entry[0x4..0x8], num);
+        BigEndian::write_u32(&mut entry[0x8..0xC], start as u32);
+
+        let mut resource = chunk(id, data);
+        start += resource.len();
+        body.append(&mut resource);
+    }
+
+    let mut blorb = b"FORM\0\0\0\0IFRS".to_vec();
+    blorb.append(&mut chunk(b"RIdx", &index));
+    blorb.append(&mut body);
+    set_form_len(&mut blorb);
+    blorb
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::io::{
+        Cursor,
+        ErrorKind,
+    };
+
+    use crate::blorb::{
+        Chunk,
+        Usage,
+    };
+    use crate::error::BlorbError;
+    use crate::io::BlorbCursor;
+    use super::*;
+
+    fn sample() -> Vec<u8> {
+        build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"png data"),
+            (b"Snd ", 3, b"OGGV", b"ogg"),
+        ], &[])
+    }
+
+    fn open(blorb: Vec<u8>) -> BlorbCursor<Cursor<Vec<u8>>> {
+        match BlorbCursor::from_file(Cursor::new(blorb)) {
+            Ok(cursor) => cursor,
+            Err(err) => panic!("failed to open blorb: {}", err),
+        }
+    }
+
+    #[test]
+    fn sample_is_valid() {
+        let mut cursor = open(sample());
+        assert!(cursor.load_resource(Usage::Pict, 1).is_ok());
+        assert!(cursor.load_resource(Usage::Snd, 3).is_ok());
+    }
+
+    #[test]
+    fn truncate_form() {
+        let blorb = corrupt_blorb(&sample(), Corruption::TruncateForm);
+        let mut cursor = open(blorb);
+        match cursor.load_resource(Usage::Pict, 1) {
+            Err(BlorbError::Io(ref err))
+                if err.kind() == ErrorKind::UnexpectedEof => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn bad_index_len() {
+        let blorb = corrupt_blorb(&sample(), Corruption::BadIndexLen);
+        match BlorbCursor::from_file(Cursor::new(blorb)).err() {
+            Some(BlorbError::BadIndexLength{num: 2, expected: 28, found: 29})
+                => (),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn dangling_fspc() {
+        let blorb = corrupt_blorb(&sample(), Corruption::DanglingFspc);
+        let mut cursor = open(blorb);
+        match cursor.load_chunk(b"Fspc") {
+            Ok(Some(Chunk::Frontispiece{num: 2})) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match cursor.frontispiece() {
+            Err(BlorbError::NotFound) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn duplicate_entry() {
+        let blorb = corrupt_blorb(&sample(), Corruption::DuplicateEntry);
+        let mut cursor = open(blorb);
+        assert_eq!(cursor.declared_count(), 3);
+        assert_eq!(cursor.total_resources(), 2);
+        assert!(cursor.load_resource(Usage::Pict, 1).is_ok());
+        assert!(cursor.load_resource(Usage::Snd, 3).is_ok());
+    }
+
+    #[test]
+    fn offset_past_end() {
+        let blorb = corrupt_blorb(&sample(), Corruption::OffsetPastEnd);
+        let mut cursor = open(blorb);
+        match cursor.load_resource(Usage::Pict, 1) {
+            Err(BlorbError::Io(ref err))
+                if err.kind() == ErrorKind::UnexpectedEof => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        match cursor.validate() {
+            Err(BlorbError::BadOffset{usage: Usage::Pict, num: 1, ..}) => (),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
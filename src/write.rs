@@ -0,0 +1,434 @@
+use std::io::{
+    Error,
+    ErrorKind,
+    Write,
+};
+
+use byteorder::{
+    BigEndian,
+    ByteOrder,
+};
+
+use crate::blorb::{
+    Chunk,
+    Usage,
+};
+use crate::error::Result;
+
+
+/// Builds a blorb file from a set of resources.
+///
+/// Resources are added to the `BlorbWriter` using
+/// `BlorbWriter::add_resource`, along with the optional metadata and
+/// frontispiece chunks. `BlorbWriter::write_to` then serializes a
+/// `FORM` of type `IFRS`, with the resource index as the first chunk,
+/// followed by the metadata and frontispiece, and then the resources
+/// in the order they were added.
+///
+/// Text chunks are always written as utf-8. As text which is not valid
+/// utf-8 is read as latin-1, rewriting a blorb holding latin-1 text
+/// keeps the text, but not the original bytes or chunk lengths. Use
+/// `BlorbCursor::load_resource_raw` where the exact bytes are needed.
+#[derive(Clone, Debug, Default)]
+pub struct BlorbWriter {
+    resources: Vec<(Usage, u32, Chunk)>,
+    metadata: Option<String>,
+    frontispiece: Option<u32>,
+}
+
+
+impl BlorbWriter {
+
+    /// Returns an empty `BlorbWriter`.
+    pub fn new() -> BlorbWriter {
+        BlorbWriter::default()
+    }
+
+    /// Adds a resource chunk with the given usage and resource number.
+    /// A resource previously added with the same usage and number is
+    /// replaced. As a blorb holds at most one executable, any previous
+    /// `Usage::Exec` resource is replaced regardless of its number.
+    pub fn add_resource(&mut self, usage: Usage, num: u32, chunk: Chunk) {
+        self.resources.retain(|&(u, n, _)| {
+            u != usage || (n != num && usage != Usage::Exec)
+        });
+        self.resources.push((usage, num, chunk));
+    }
+
+    /// Sets the xml metadata, written as an `IFmd` chunk.
+    pub fn set_metadata(&mut self, info: String) {
+        self.metadata = Some(info);
+    }
+
+    /// Sets the picture resource number of the frontispiece, written as
+    /// an `Fspc` chunk.
+    pub fn set_frontispiece(&mut self, num: u32) {
+        self.frontispiece = Some(num);
+    }
+
+    /// Serializes the blorb, and writes it to the given destination. A
+    /// `BlorbError` is returned if there is an exception writing to the
+    /// destination, or if the blorb would be too large for its 32 bit
+    /// chunk offsets.
+    pub fn write_to<W: Write>(&self, dest: W) -> Result<()> {
+        let mut dest = dest;
+
+        let mut chunks = Vec::new();
+        if let Some(ref info) = self.metadata {
+            chunks.push(chunk_parts(&Chunk::Metadata{info: info.clone()}));
+        }
+        if let Some(num) = self.frontispiece {
+            chunks.push(chunk_parts(&Chunk::Frontispiece{num}));
+        }
+        let resources: Vec<_> = self.resources.iter()
+            .map(|(_, _, chunk)| chunk_parts(chunk))
+            .collect();
+
+        // the resource index is written first, so each resource starts
+        // after the form header, the index, and the preceding chunks.
+        let index_len = 0x4 + self.resources.len() as u64 * 0xC;
+        let mut start = 0xC + 0x8 + index_len;
+        for (_, body) in &chunks {
+            start += padded_len(body);
+        }
+
+        let mut index = vec![0x0; index_len as usize];
+        BigEndian::write_u32(&mut index[0x0..0x4], self.resources.len() as u32);
+        for (i, &(usage, num, _)) in self.resources.iter().enumerate() {
+            let entry = &mut index[0x4 + i * 0xC..0x4 + (i + 1) * 0xC];
+            entry[0x0..0x4].copy_from_slice(&usage.as_bytes());
+            BigEndian::write_u32(&mut entry[0x4..0x8], num);
+            BigEndian::write_u32(&mut entry[0x8..0xC], checked_u32(start)?);
+            start += padded_len(&resources[i].1);
+        }
+
+        let mut form = [0x0; 0xC];
+        form[0x0..0x4].copy_from_slice(b"FORM");
+        BigEndian::write_u32(&mut form[0x4..0x8], checked_u32(start - 0x8)?);
+        form[0x8..0xC].copy_from_slice(b"IFRS");
+        dest.write_all(&form)?;
+
+        write_chunk(&mut dest, b"RIdx", &index)?;
+        for (id, body) in chunks.iter().chain(&resources) {
+            write_chunk(&mut dest, id, body)?;
+        }
+        dest.flush()?;
+        Ok(())
+    }
+}
+
+
+/// Returns the space taken by a chunk with the given body, including
+/// the chunk header and the pad byte for odd length bodies.
+fn padded_len(body: &[u8]) -> u64 {
+    let len = body.len() as u64;
+    0x8 + len + (len & 1)
+}
+
+
+/// Converts a blorb offset or length into its 32 bit representation.
+/// Returns a `BlorbError` if the value does not fit.
+fn checked_u32(value: u64) -> Result<u32> {
+    if value > u32::MAX as u64 {
+        return Err(Error::new(ErrorKind::InvalidInput,
+            "blorb too large for 32 bit chunk offsets").into());
+    }
+    Ok(value as u32)
+}
+
+
+/// Writes a chunk with the given identifier and body, including the
+/// pad byte for odd length bodies.
+fn write_chunk<W: Write>(dest: &mut W, id: &[u8; 0x4], body: &[u8])
+        -> Result<()> {
+    let mut header = [0x0; 0x8];
+    header[0x0..0x4].copy_from_slice(id);
+    let len = checked_u32(body.len() as u64)?;
+    BigEndian::write_u32(&mut header[0x4..0x8], len);
+    dest.write_all(&header)?;
+    dest.write_all(body)?;
+    if body.len() & 1 == 1 {dest.write_all(&[0x0])?};
+    Ok(())
+}
+
+
+/// Returns the chunk identifier and chunk body a `Chunk` is stored as.
+/// This is the reverse of the reading done by `BlorbCursor`.
+fn chunk_parts(chunk: &Chunk) -> ([u8; 0x4], Vec<u8>) {
+    match *chunk {
+        Chunk::Unknown{ref meta, ref data} => (meta.id, data.clone()),
+        Chunk::UnknownForm{ref meta, ref data} => {
+            let mut body = meta.id.to_vec();
+            body.extend_from_slice(data);
+            (*b"FORM", body)
+        },
+        Chunk::ResourceIndex{ref index} => {
+            let entries: Vec<_> = index.entries().collect();
+            let mut body = vec![0x0; 0x4 + entries.len() * 0xC];
+            BigEndian::write_u32(&mut body[0x0..0x4], entries.len() as u32);
+            for (i, entry) in entries.iter().enumerate() {
+                let bytes = &mut body[0x4 + i * 0xC..0x4 + (i + 1) * 0xC];
+                bytes[0x0..0x4].copy_from_slice(&entry.usage.as_bytes());
+                BigEndian::write_u32(&mut bytes[0x4..0x8], entry.num);
+                BigEndian::write_u32(&mut bytes[0x8..0xC], entry.start);
+            }
+            (*b"RIdx", body)
+        },
+        Chunk::Metadata{ref info} => (*b"IFmd", info.clone().into_bytes()),
+        Chunk::Frontispiece{num} => {
+            let mut body = vec![0x0; 0x4];
+            BigEndian::write_u32(&mut body, num);
+            (*b"Fspc", body)
+        },
+        Chunk::Resolution{px, py, minx, miny, maxx, maxy, ref images} => {
+            let mut images: Vec<_> = images.values().collect();
+            images.sort_by_key(|image| image.num);
+
+            let mut values = vec![px, py, minx, miny, maxx, maxy];
+            for image in images {
+                values.extend_from_slice(&[
+                    image.num,
+                    image.ratnum, image.ratden,
+                    image.minnum, image.minden,
+                    image.maxnum, image.maxden,
+                ]);
+            }
+            let mut body = vec![0x0; values.len() * 0x4];
+            for (bytes, value) in body.chunks_mut(0x4).zip(values) {
+                BigEndian::write_u32(bytes, value);
+            }
+            (*b"Reso", body)
+        },
+        Chunk::Author{ref text} => (*b"AUTH", text.clone().into_bytes()),
+        Chunk::Copyright{ref text} => (*b"(c) ", text.clone().into_bytes()),
+        Chunk::Annotation{ref text} => (*b"ANNO", text.clone().into_bytes()),
+        Chunk::ReleaseNumber{number} => {
+            let mut body = vec![0x0; 0x2];
+            BigEndian::write_u16(&mut body, number);
+            (*b"RelN", body)
+        },
+        Chunk::AdaptivePalette{ref entries} => {
+            let mut body = vec![0x0; entries.len() * 0x4];
+            for (bytes, &num) in body.chunks_mut(0x4).zip(entries) {
+                BigEndian::write_u32(bytes, num);
+            }
+            (*b"APal", body)
+        },
+        Chunk::Palette{ref colors} => {
+            let body = colors.iter()
+                .flat_map(|&(r, g, b)| vec![r, g, b])
+                .collect();
+            (*b"Plte", body)
+        },
+        Chunk::DirectPalette{depth} => (*b"Plte", vec![depth]),
+        Chunk::Debug{ref data} => (*b"Dbug", data.clone()),
+        Chunk::ShortName{ref name} => {
+            let units: Vec<u16> = name.encode_utf16().collect();
+            let mut body = vec![0x0; units.len() * 0x2];
+            for (bytes, &unit) in body.chunks_mut(0x2).zip(&units) {
+                BigEndian::write_u16(bytes, unit);
+            }
+            (*b"SNam", body)
+        },
+        Chunk::ZCode{ref code} => (*b"ZCOD", code.clone()),
+        Chunk::Glulx{ref code} => (*b"GLUL", code.clone()),
+        Chunk::Tads2{ref code} => (*b"TAD2", code.clone()),
+        Chunk::Tads3{ref code} => (*b"TAD3", code.clone()),
+        Chunk::Hugo{ref code} => (*b"HUGO", code.clone()),
+        Chunk::Alan{ref code} => (*b"ALAN", code.clone()),
+        Chunk::Adrift{ref code} => (*b"ADRI", code.clone()),
+        Chunk::Level9{ref code} => (*b"LEVE", code.clone()),
+        Chunk::Agt{ref code} => (*b"AGT ", code.clone()),
+        Chunk::MagneticScrolls{ref code} => (*b"MAGS", code.clone()),
+        Chunk::AdvSys{ref code} => (*b"ADVS", code.clone()),
+        Chunk::Exec{ref code} => (*b"EXEC", code.clone()),
+        Chunk::Png{ref data} => (*b"PNG ", data.clone()),
+        Chunk::Jpeg{ref data} => (*b"JPEG", data.clone()),
+        Chunk::Rectangle{width, height} => {
+            let mut body = vec![0x0; 0x8];
+            BigEndian::write_u32(&mut body[0x0..0x4], width);
+            BigEndian::write_u32(&mut body[0x4..0x8], height);
+            (*b"Rect", body)
+        },
+        // `Chunk::Aiff` holds the full form, including the chunk header
+        Chunk::Aiff{ref data} => {
+            (*b"FORM", data.get(0x8..).unwrap_or(&[]).to_vec())
+        },
+        Chunk::Ogg{ref data} => (*b"OGGV", data.clone()),
+        Chunk::Mod{ref data} => (*b"MOD ", data.clone()),
+        Chunk::Song{ref data} => (*b"SONG", data.clone()),
+        Chunk::Text{ref text} => (*b"TEXT", text.clone().into_bytes()),
+        Chunk::Binary{ref data} => (*b"BINA", data.clone()),
+        Chunk::Gif{ref data} => (*b"GIF ", data.clone()),
+        Chunk::Wav{ref data} => (*b"WAV ", data.clone()),
+        Chunk::Midi{ref data} => (*b"MIDI", data.clone()),
+        Chunk::Mp3{ref data} => (*b"MP3 ", data.clone()),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::blorb::{
+        ImageResolution,
+        IndexEntry,
+    };
+    use crate::io::BlorbCursor;
+    use crate::testutil::build_blorb;
+    use super::*;
+
+    #[test]
+    fn write_round_trip() {
+        let aiff = b"FORM\0\0\0\x0EAIFFSSND\0\0\0\x02\x01\x02".to_vec();
+        let mut images = HashMap::new();
+        images.insert(1, ImageResolution{
+            num: 1, ratnum: 1, ratden: 2, minnum: 0, minden: 0,
+            maxnum: 0, maxden: 0,
+        });
+
+        let mut writer = BlorbWriter::new();
+        writer.add_resource(Usage::Pict, 1, Chunk::Png{data: b"odd".to_vec()});
+        writer.add_resource(Usage::Pict, 2, Chunk::Rectangle{
+            width: 640, height: 480,
+        });
+        writer.add_resource(Usage::Snd, 3, Chunk::Aiff{data: aiff.clone()});
+        writer.add_resource(Usage::Data, 4, Chunk::Text{text: "text".into()});
+        writer.add_resource(Usage::Exec, 0, Chunk::Glulx{code: vec![0x1; 5]});
+        writer.add_resource(Usage::Data, 5, Chunk::Resolution{
+            px: 1, py: 2, minx: 3, miny: 4, maxx: 5, maxy: 6,
+            images,
+        });
+        writer.set_metadata("<ifindex/>".into());
+        writer.set_frontispiece(1);
+
+        let mut blorb = Vec::new();
+        writer.write_to(&mut blorb).unwrap();
+        assert_eq!(blorb.len() & 1, 0);
+
+        let mut cursor = BlorbCursor::from_bytes(blorb).unwrap();
+        cursor.validate().unwrap();
+        assert_eq!(cursor.entries().count(), 6);
+
+        match cursor.load_resource(Usage::Pict, 1).unwrap() {
+            Chunk::Png{data} => assert_eq!(data, b"odd"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_resource(Usage::Pict, 2).unwrap() {
+            Chunk::Rectangle{width: 640, height: 480} => (),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_resource(Usage::Snd, 3).unwrap() {
+            Chunk::Aiff{data} => assert_eq!(data, aiff),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_resource(Usage::Data, 4).unwrap() {
+            Chunk::Text{text} => assert_eq!(text, "text"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_resource(Usage::Exec, 0).unwrap() {
+            Chunk::Glulx{code} => assert_eq!(code, vec![0x1; 5]),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_resource(Usage::Data, 5).unwrap() {
+            Chunk::Resolution{px: 1, maxy: 6, ref images, ..} =>
+                assert_eq!(images[&1].ratden, 2),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_chunk(b"IFmd").unwrap() {
+            Some(Chunk::Metadata{info}) => assert_eq!(info, "<ifindex/>"),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.frontispiece().unwrap() {
+            Some(Chunk::Png{..}) => (),
+            other => panic!("unexpected frontispiece: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn add_resource_replaces() {
+        let mut writer = BlorbWriter::new();
+        writer.add_resource(Usage::Pict, 1, Chunk::Png{data: vec![0x1]});
+        writer.add_resource(Usage::Pict, 1, Chunk::Png{data: vec![0x2]});
+        writer.add_resource(Usage::Exec, 0, Chunk::ZCode{code: vec![0x3]});
+        writer.add_resource(Usage::Exec, 1, Chunk::Glulx{code: vec![0x4]});
+
+        let mut blorb = Vec::new();
+        writer.write_to(&mut blorb).unwrap();
+        let mut cursor = BlorbCursor::from_bytes(blorb).unwrap();
+        assert_eq!(cursor.entries().count(), 2);
+        match cursor.load_resource(Usage::Pict, 1).unwrap() {
+            Chunk::Png{data} => assert_eq!(data, vec![0x2]),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+        match cursor.load_resource(Usage::Exec, 1).unwrap() {
+            Chunk::Glulx{..} => (),
+            other => panic!("unexpected chunk: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rewrite_keeps_declared_lengths() {
+        let aiff = b"AIFFSSND\0\0\0\x03\x01\x02\x03";
+        let blorb = build_blorb(&[
+            (b"Pict", 1, b"PNG ", b"odd"),
+            (b"Pict", 2, b"JPEG", b"even"),
+            (b"Snd ", 1, b"FORM", aiff),
+            (b"Data", 1, b"TEXT", b"text."),
+        ], &[]);
+        let mut original = BlorbCursor::from_bytes(blorb.clone()).unwrap();
+
+        let entries: Vec<IndexEntry> = original.entries().cloned().collect();
+        let mut writer = BlorbWriter::new();
+        for entry in &entries {
+            let chunk = original.load_entry(entry).unwrap();
+            writer.add_resource(entry.usage, entry.num, chunk);
+        }
+        let mut rewritten = Vec::new();
+        writer.write_to(&mut rewritten).unwrap();
+        let mut rewritten = BlorbCursor::from_bytes(rewritten).unwrap();
+
+        for entry in &entries {
+            // the declared length, as stored in the original blorb
+            let start = entry.start as usize;
+            let declared = BigEndian::read_u32(&blorb[start + 0x4..][..0x4]);
+
+            let info = original.resource_info(entry.usage, entry.num).unwrap();
+            assert_eq!(info.len, declared);
+            let info = rewritten.resource_info(entry.usage, entry.num).unwrap();
+            assert_eq!(info.len, declared);
+        }
+    }
+
+    #[test]
+    fn chunk_parts_direct_palette() {
+        for &depth in &[16, 32] {
+            let (id, body) = chunk_parts(&Chunk::DirectPalette{depth});
+            assert_eq!(&id, b"Plte");
+            assert_eq!(body, vec![depth]);
+        }
+    }
+
+    #[test]
+    fn rewrite_latin1_text_as_utf8() {
+        let blorb = build_blorb(&[(b"Data", 1, b"TEXT", b"caf\xE9")], &[]);
+        let mut original = BlorbCursor::from_bytes(blorb).unwrap();
+        let text = original.load_resource(Usage::Data, 1).unwrap();
+
+        let mut writer = BlorbWriter::new();
+        writer.add_resource(Usage::Data, 1, text.clone());
+        let mut rewritten = Vec::new();
+        writer.write_to(&mut rewritten).unwrap();
+        let mut rewritten = BlorbCursor::from_bytes(rewritten).unwrap();
+
+        // the text is kept, but is re-encoded as utf-8
+        assert_eq!(rewritten.load_resource(Usage::Data, 1).unwrap(), text);
+        let (_, data) = original.load_resource_raw(Usage::Data, 1).unwrap();
+        assert_eq!(data, b"caf\xE9");
+        let (meta, data) =
+            rewritten.load_resource_raw(Usage::Data, 1).unwrap();
+        assert_eq!(meta.len, 5);
+        assert_eq!(data, "caf\u{E9}".as_bytes());
+    }
+}